@@ -2,13 +2,98 @@
 // Transparent dog rescue donation tracking on Stellar blockchain
 
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token, Address, Env, String, Vec,
+    contract, contracterror, contractevent, contractimpl, contracttype, token, Address, BytesN,
+    Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
 };
 
+// Storage schema written by this build; bump when a stored record layout changes
+// and add the matching step to `migrate_step`.
+const SCHEMA_VERSION: u32 = 20;
+
+// Upper bound on records returned by a single list query
+const MAX_PAGE_SIZE: u32 = 50;
+
+// Upper bound on recipients of a single split donation
+const MAX_SPLIT_RECIPIENTS: u32 = 20;
+
+// Basis points making up a whole donation
+const BPS_DENOMINATOR: u32 = 10_000;
+
+// Hard cap on the platform fee, whatever the admin configures (5%)
+const MAX_FEE_BPS: u32 = 500;
+
+// Ids per storage entry in a secondary index
+const INDEX_BUCKET_SIZE: u64 = 100;
+
+// Storage TTLs, in ledgers (~5s each)
+const DAY_IN_LEDGERS: u32 = 17280;
+const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+// Contract Errors
+// Codes are part of the public interface: append new variants, never renumber.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PawChainError {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    Unauthorized = 3,
+    FeederNotFound = 4,
+    DogNotFound = 5,
+    DonationNotFound = 6,
+    ExpenseNotFound = 7,
+    TreatmentNotFound = 8,
+    InvalidAmount = 9,
+    FeederAlreadyRegistered = 10,
+    SchemaUpToDate = 11,
+    NoPendingAdmin = 12,
+    DuplicateDog = 13,
+    ExpenseAlreadyVerified = 14,
+    InvalidDeadline = 15,
+    CampaignNotFound = 16,
+    CampaignClosed = 17,
+    CampaignStillActive = 18,
+    NothingToRefund = 19,
+    InvalidInterval = 20,
+    SubscriptionNotFound = 21,
+    SubscriptionInactive = 22,
+    PaymentNotDue = 23,
+    InsufficientAllowance = 24,
+    TokenNotAllowed = 25,
+    InvalidSplit = 26,
+    DogInactive = 27,
+    SponsorshipNotFound = 28,
+    AlreadySponsoring = 29,
+    SponsorshipInactive = 30,
+    InvalidMatchingPool = 31,
+    MatchingPoolNotFound = 32,
+    MatchingPoolStillActive = 33,
+    FeeTooHigh = 34,
+    DogNotCritical = 35,
+    EmergencyAlreadyOpen = 36,
+    EmergencyNotFound = 37,
+    ExpenseNotPending = 38,
+    AuditorNotFound = 39,
+    AuditorAlreadyRegistered = 40,
+    AlreadyAttested = 41,
+    InsufficientBalance = 42,
+    UnknownCategory = 43,
+    CategoryAlreadyRegistered = 44,
+    VetNotFound = 45,
+    VetAlreadyRegistered = 46,
+    VetNotVerified = 47,
+    SchemaNotMigratable = 48,
+    AttestationNotFound = 49,
+}
+
 // Dog Profile Structure
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DogProfile {
     pub dog_id: u64,
     pub name: String,
@@ -20,12 +105,13 @@ pub struct DogProfile {
     pub feeder_id: u64,        // ID of the feeder/NGO managing this dog
     pub registered_date: u64,
     pub last_updated: u64,
-    pub is_active: bool, // false if adopted or deceased
+    pub is_active: bool,          // false if adopted or deceased
+    pub departed_at: Option<u64>, // When the dog was adopted or deceased
 }
 
 // Feeder/NGO Profile Structure
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FeederProfile {
     pub feeder_id: u64,
     pub name: String,
@@ -35,43 +121,91 @@ pub struct FeederProfile {
     pub registration_number: String, // For NGOs/Charities
     pub contact_info: String,
     pub registered_date: u64,
-    pub is_verified: bool, // Admin verification status
-    pub total_received: i128,
-    pub total_spent: i128,
+    pub is_verified: bool,                  // Admin verification status
+    pub total_received: Map<Address, i128>, // Per token
+    pub total_spent: Map<Address, i128>,    // Per token
 }
 
 // Donation Record
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Donation {
     pub donation_id: u64,
     pub donor_address: Address,
     pub feeder_id: u64,
-    pub amount: i128,
+    pub token: Address,
+    pub amount: i128, // Gross amount given by the donor, fee included
+    pub fee: i128,    // Platform fee sent to the treasury
     pub timestamp: u64,
     pub purpose: String, // "General", "Specific Dog", "Emergency", "Vaccination", etc.
     pub dog_id: Option<u64>, // If donation is for a specific dog
     pub transaction_hash: String,
+    pub escrowed: bool, // Held by the contract until the feeder's expenses are verified
+    pub batch_id: Option<u64>, // Shared by the donations of one `donate_split` call
+    pub match_of: Option<u64>, // For matching-pool donations, the donation being matched
 }
 
 // Expense/Usage Record
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ExpenseRecord {
     pub expense_id: u64,
     pub feeder_id: u64,
+    pub token: Address,
     pub amount: i128,
-    pub category: String, // "Food", "Vaccination", "Treatment", "Spaying", "Neutering", "Medicine"
+    pub funding_source: FundingSource,
+    pub category: ExpenseCategory,
     pub description: String,
     pub timestamp: u64,
-    pub receipt_hash: String, // IPFS hash of receipt
-    pub dogs_affected: Vec<u64>, // List of dog IDs that benefited
-    pub verified: bool,
+    pub receipt_hash: String,       // IPFS hash of receipt
+    pub dogs_affected: Vec<u64>,    // List of dog IDs that benefited
+    pub fund_draws: Map<u64, i128>, // Per dog amount taken from its earmarked fund
+    pub status: ExpenseStatus,
+    pub rejection_reason: Option<String>,
+}
+
+// Where the money for an expense came from
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FundingSource {
+    PawChain,   // Donations received through the contract
+    SelfFunded, // The feeder's own money
+    External,   // Grants or donations received outside PawChain
+}
+
+// Spend recorded by a feeder, split by funding source; per token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeederLedger {
+    pub pawchain_spent: Map<Address, i128>,
+    pub external_spent: Map<Address, i128>, // Self-funded and external spend
+}
+
+// A feeder's PawChain donations reconciled against the spend charged to them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeederBalance {
+    pub feeder_id: u64,
+    pub token: Address,
+    pub received: i128,       // Net donations, escrow included
+    pub escrowed: i128,       // Part of `received` still held by the contract
+    pub pawchain_spent: i128, // Expenses funded by those donations
+    pub external_spent: i128, // Expenses funded from elsewhere
+    pub available: i128,      // received - pawchain_spent
+}
+
+// Review state of an expense
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExpenseStatus {
+    Pending,  // Awaiting review; counted in totals and stats
+    Verified, // Receipt checked; may release escrow
+    Rejected, // Reversed out of totals and stats
 }
 
 // Activity Statistics
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ActivityStats {
     pub feeder_id: u64,
     pub dogs_fed: u64,
@@ -84,37 +218,711 @@ pub struct ActivityStats {
     pub last_updated: u64,
 }
 
+// ActivityStats counter an expense category adds its dogs to
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ActivityCounter {
+    Fed,
+    Vaccinated,
+    Spayed,
+    Neutered,
+    Treated,
+    Rescued,
+    Adopted,
+}
+
+// What an expense paid for. Custom categories must be registered by the admin.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExpenseCategory {
+    Food,
+    Vaccination,
+    Spaying,
+    Neutering,
+    Treatment,
+    Medicine,
+    Custom(String),
+}
+
 // Medical Treatment Record
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TreatmentRecord {
     pub treatment_id: u64,
     pub dog_id: u64,
     pub feeder_id: u64,
     pub treatment_type: String,
     pub description: String,
-    pub cost: i128,
+    pub cost: i128, // In the default token
+    pub funding_source: FundingSource,
     pub date: u64,
-    pub veterinarian: String,
-    pub outcome: String, // "Successful", "Ongoing", "Failed"
+    pub veterinarian: String, // Free-text name; the vet's registered name when `vet_id` is set
+    pub vet_id: Option<u64>,
+    pub vet_attested: bool, // Signed by the referenced vet, at recording or later
+    pub outcome: String,    // "Successful", "Ongoing", "Failed"
+}
+
+// Registered veterinarian who can sign treatment records
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VetProfile {
+    pub vet_id: u64,
+    pub name: String,
+    pub license_number: String,
+    pub clinic: String,
+    pub wallet_address: Address,
+    pub registered_date: u64,
+    pub is_verified: bool, // Admin has checked the license
+}
+
+// Running spend attributed to a single dog
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DogSpend {
+    pub treatment_cost: i128,              // Sum of treatment costs
+    pub expense_share: Map<Address, i128>, // Per token sum of expense amounts split evenly across dogs_affected
+}
+
+// Emergency Appeal Status
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EmergencyStatus {
+    Open,          // Counting "Emergency" donations for the dog until the deadline
+    TargetReached, // Closed once raised met the target
+    HealthChanged, // Closed when the dog's health status changed
+    Expired,       // Closed after the deadline passed
+    DogDeparted,   // Closed when the dog was adopted or died
+}
+
+// Time-boxed appeal for a dog in critical condition
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyAppeal {
+    pub appeal_id: u64,
+    pub dog_id: u64,
+    pub feeder_id: u64,
+    pub token: Address,
+    pub target: i128,
+    pub raised: i128, // Net "Emergency" donations for the dog in `token`
+    pub opened_at: u64,
+    pub deadline: u64,
+    pub closed_at: Option<u64>,
+    pub status: EmergencyStatus,
+}
+
+// Independent party (vet, accountant) trusted to attest records
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auditor {
+    pub address: Address,
+    pub name: String,
+    pub added_at: u64,
+    pub attestation_count: u64,
+    pub is_active: bool, // false once removed by the admin
+}
+
+// Record an attestation vouches for
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttestationSubject {
+    Expense(u64),
+    Treatment(u64),
+}
+
+// An auditor's sign-off on an expense or treatment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub attestation_id: u64,
+    pub auditor: Address,
+    pub subject: AttestationSubject,
+    pub feeder_id: u64,
+    pub timestamp: u64,
+    pub note_hash: String, // IPFS hash of the auditor's notes
+}
+
+// How much independent checking a feeder's records have had
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeederAuditSummary {
+    pub feeder_id: u64,
+    pub attestations: u64,
+    pub distinct_auditors: u64,
+}
+
+// Fundraising Campaign Status
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CampaignStatus {
+    Active,     // Accepting contributions until the deadline
+    Successful, // Goal met; funds paid out to the feeder
+    Failed,     // Goal missed; contributors can claim refunds
+}
+
+// Fundraising Campaign
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Campaign {
+    pub campaign_id: u64,
+    pub feeder_id: u64,
+    pub dog_id: Option<u64>, // If the appeal is for a specific dog
+    pub title: String,
+    pub goal: i128,
+    pub raised: i128, // Held by the contract until the campaign is finalized
+    pub deadline: u64,
+    pub created_at: u64,
+    pub status: CampaignStatus,
+    pub unrecorded: i128, // Part of a paid-out `raised` not yet recorded as donations
+    pub unrecorded_fee: i128, // Part of the payout fee not yet charged to those donations
+}
+
+// Platform fee taken from every donation paid to a feeder
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub fee_bps: u32, // At most MAX_FEE_BPS
+    pub treasury: Address,
+}
+
+// Sponsor funds held by the contract to match donations to eligible feeders
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchingPool {
+    pub pool_id: u64,
+    pub sponsor: Address,
+    pub token: Address,
+    pub cap: i128,       // Total funded by the sponsor
+    pub remaining: i128, // Still available for matching or reclaim
+    pub match_bps: u32,  // Matched amount per donated amount; 10000 = 1:1
+    pub eligible_feeders: Vec<u64>,
+    pub expires_at: u64,
+    pub created_at: u64,
+}
+
+// Recurring Donation, pulled from the donor's token allowance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub subscription_id: u64,
+    pub donor: Address,
+    pub feeder_id: u64,
+    pub dog_id: Option<u64>,
+    pub amount: i128,          // Pulled once per interval
+    pub interval: u64,         // Seconds between payments
+    pub next_payment_due: u64, // Timestamp from which the next pull may happen
+    pub payments_made: u64,
+    pub created_at: u64,
+    pub is_active: bool, // false once cancelled
+}
+
+// Why a dog left the programme
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DogExitReason {
+    Adopted,
+    Deceased,
+}
+
+// A donor's ongoing pledge to a specific dog
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sponsorship {
+    pub sponsorship_id: u64,
+    pub donor: Address,
+    pub dog_id: u64,
+    pub monthly_amount: i128, // Pledged amount per month
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+    pub is_active: bool, // false once ended by the donor or the dog leaving
+}
+
+// Lifetime giving of a single donor
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DonorSummary {
+    pub donor: Address,
+    pub total_given: Map<Address, i128>, // Per token
+    pub donation_count: u64,
+    pub feeders_supported: u64, // Distinct feeders donated to
+    pub dogs_supported: u64,    // Distinct dogs named in a donation
+    pub last_donation: u64,
+}
+
+// Dog profile with its medical and spending history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DogDossier {
+    pub profile: DogProfile,
+    pub treatments: Vec<TreatmentRecord>, // Most recent MAX_PAGE_SIZE treatments
+    pub expenses: Vec<ExpenseRecord>,     // Most recent MAX_PAGE_SIZE linked expenses
+    pub total_treatments: u64,
+    pub total_expenses: u64,
+    pub spend: DogSpend,
+}
+
+// ========== EVENTS ==========
+// Topics carry the ids indexers filter on; the data is the full record after the change.
+
+#[contractevent(topics = ["dog_registered"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DogRegistered {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub dog_id: u64,
+    pub dog: DogProfile,
+}
+
+#[contractevent(topics = ["dog_health"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DogHealthUpdated {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub dog_id: u64,
+    pub dog: DogProfile,
+}
+
+#[contractevent(topics = ["dog_inactive"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DogDeactivated {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub dog_id: u64,
+    pub reason: DogExitReason,
+}
+
+#[contractevent(topics = ["dog_fund_released"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DogFundReleased {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub dog_id: u64,
+    pub amounts: Map<Address, i128>, // Per token, moved to the feeder's general fund
+}
+
+#[contractevent(topics = ["emergency_opened"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyOpened {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub dog_id: u64,
+    pub appeal: EmergencyAppeal,
+}
+
+#[contractevent(topics = ["emergency_closed"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyClosed {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub dog_id: u64,
+    pub appeal: EmergencyAppeal,
+}
+
+#[contractevent(topics = ["feeder_registered"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeederRegistered {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub wallet_address: Address,
+    pub feeder: FeederProfile,
+}
+
+#[contractevent(topics = ["feeder_verified"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeederVerified {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub admin: Address,
+    pub feeder: FeederProfile,
+}
+
+#[contractevent(topics = ["donation"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DonationReceived {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub donor: Address,
+    pub donation: Donation,
+}
+
+#[contractevent(topics = ["expense"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpenseRecorded {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub expense_id: u64,
+    pub expense: ExpenseRecord,
+}
+
+#[contractevent(topics = ["treatment"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreatmentRecorded {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub dog_id: u64,
+    pub treatment: TreatmentRecord,
+}
+
+#[contractevent(topics = ["campaign_created"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignCreated {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub campaign_id: u64,
+    pub campaign: Campaign,
+}
+
+#[contractevent(topics = ["campaign_contribution"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignContributed {
+    #[topic]
+    pub campaign_id: u64,
+    #[topic]
+    pub donor: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["campaign_finalized"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignFinalized {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub campaign_id: u64,
+    pub campaign: Campaign,
+}
+
+#[contractevent(topics = ["campaign_refund"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignRefunded {
+    #[topic]
+    pub campaign_id: u64,
+    #[topic]
+    pub donor: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["matching_pool_created"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchingPoolCreated {
+    #[topic]
+    pub pool_id: u64,
+    #[topic]
+    pub sponsor: Address,
+    pub pool: MatchingPool,
+}
+
+#[contractevent(topics = ["matching_pool_reclaimed"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchingPoolReclaimed {
+    #[topic]
+    pub pool_id: u64,
+    #[topic]
+    pub sponsor: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["subscription_created"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionCreated {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub donor: Address,
+    pub subscription: Subscription,
+}
+
+#[contractevent(topics = ["subscription_cancelled"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionCancelled {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub donor: Address,
+    pub subscription: Subscription,
+}
+
+#[contractevent(topics = ["sponsorship_started"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SponsorshipStarted {
+    #[topic]
+    pub dog_id: u64,
+    #[topic]
+    pub donor: Address,
+    pub sponsorship: Sponsorship,
+}
+
+#[contractevent(topics = ["sponsorship_ended"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SponsorshipEnded {
+    #[topic]
+    pub dog_id: u64,
+    #[topic]
+    pub donor: Address,
+    pub sponsorship: Sponsorship,
+}
+
+#[contractevent(topics = ["expense_verified"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpenseVerified {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub expense_id: u64,
+    pub expense: ExpenseRecord,
+}
+
+#[contractevent(topics = ["expense_rejected"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpenseRejected {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub expense_id: u64,
+    pub expense: ExpenseRecord,
+}
+
+#[contractevent(topics = ["escrow_released"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowReleased {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub expense_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["token_allowlist"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenAllowlistUpdated {
+    #[topic]
+    pub token: Address,
+    pub allowed: bool,
+}
+
+#[contractevent(topics = ["fee_config"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfigUpdated {
+    #[topic]
+    pub admin: Address,
+    pub config: FeeConfig,
+}
+
+#[contractevent(topics = ["auditor_registry"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditorRegistryUpdated {
+    #[topic]
+    pub auditor: Address,
+    pub active: bool,
+}
+
+#[contractevent(topics = ["attested"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordAttested {
+    #[topic]
+    pub feeder_id: u64,
+    #[topic]
+    pub auditor: Address,
+    pub attestation: Attestation,
+}
+
+#[contractevent(topics = ["category_registered"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryRegistered {
+    #[topic]
+    pub name: String,
+    pub counter: Option<ActivityCounter>,
+}
+
+#[contractevent(topics = ["vet_registered"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VetRegistered {
+    #[topic]
+    pub vet_id: u64,
+    #[topic]
+    pub wallet_address: Address,
+    pub vet: VetProfile,
+}
+
+#[contractevent(topics = ["vet_verified"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VetVerified {
+    #[topic]
+    pub vet_id: u64,
+    #[topic]
+    pub admin: Address,
+    pub vet: VetProfile,
+}
+
+#[contractevent(topics = ["vet_attested"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreatmentVetAttested {
+    #[topic]
+    pub vet_id: u64,
+    #[topic]
+    pub treatment_id: u64,
+}
+
+#[contractevent(topics = ["upgraded"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUpgraded {
+    #[topic]
+    pub admin: Address,
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent(topics = ["migrated"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SchemaMigrated {
+    #[topic]
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[contractevent(topics = ["admin_proposed"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminProposed {
+    #[topic]
+    pub admin: Address,
+    pub proposed_admin: Address,
+}
+
+#[contractevent(topics = ["admin_changed"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminChanged {
+    #[topic]
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+// Secondary indexes: append-only id lists (addresses for CampaignContributors
+// and Auditors), stored in buckets of INDEX_BUCKET_SIZE entries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IndexKey {
+    FeederDogs(u64),
+    FeederDonations(u64),
+    FeederExpenses(u64),
+    DogTreatments(u64),
+    DogExpenses(u64),
+    DonorDonations(Address),
+    BatchDonations(u64),
+    DogSponsorships(u64),
+    DonorSponsorships(Address),
+    SubjectAttestations(AttestationSubject),
+    CampaignContributors(u64),
+    PendingExpenses, // Review queue: every expense in the order it was recorded
+    Auditors,        // Every address ever registered as an auditor
+    Emergencies,     // Every appeal in the order it was opened
+}
+
+// Persistent keys for the auditor registry and attestations
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuditKey {
+    Auditor(Address),
+    Attestation(u64),
+    Attested(AttestationSubject, Address), // Flag: auditor has attested the record
+    FeederAudit(u64),
+    FeederAuditor(u64, Address), // Flag: auditor has attested one of the feeder's records
+}
+
+// Persistent keys for the veterinarian registry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VetKey {
+    Profile(u64),
+    ByAddress(Address),
+}
+
+// Admin-managed config in instance storage, nested to keep DataKey under the
+// 50-variant limit of contract types.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigKey {
+    AllowedTokens,
+    FeeConfig,
+    FeeTotals,        // Per token fees collected
+    CustomCategories, // Custom expense category name -> counter it increments
+}
+
+// Keys of storage layouts that migrations have since replaced. Variant names
+// match the DataKey variants they were taken from, so they address the same entries.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LegacyKey {
+    PendingExpenses, // Before v15: ids of expenses awaiting review, now IndexKey::PendingExpenses
+    OpenEmergencies, // Before v19: ids of appeals not yet closed, now IndexKey::Emergencies
 }
 
 // Storage Keys
+// Counters and config live in instance storage; per-record entries are persistent.
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
+    // Instance
     DogCount,
     FeederCount,
     DonationCount,
     ExpenseCount,
+    CampaignCount,
+    SubscriptionCount,
+    BatchCount,
+    SponsorshipCount,
+    MatchingPoolCount,
+    EmergencyCount,
+    AttestationCount,
+    Auditors, // Before v16: every auditor address, now IndexKey::Auditors
     TreatmentCount,
+    VetCount,
+    Admin,
+    PendingAdmin,
+    TokenAddress, // Default token, used by campaigns and subscriptions
+    Config(ConfigKey),
+    SchemaVersion,
+    MigrationCursor,
+    // Persistent
     Dog(u64),
     Feeder(u64),
     Donation(u64),
     Expense(u64),
     Treatment(u64),
     FeederStats(u64),
-    Admin,
-    TokenAddress,
+    FeederByAddress(Address),
+    DogSpend(u64),
+    DogFund(u64),     // Per token unspent donations earmarked for the dog
+    GeneralFund(u64), // Per token earmarks released to a feeder by dogs leaving
+    EscrowBalance(u64),
+    Campaign(u64),
+    CampaignContribution(u64, Address),
+    Subscription(u64),
+    Sponsorship(u64),
+    MatchingPool(u64),
+    Emergency(u64),
+    DogEmergency(u64),               // dog_id -> open appeal id
+    EmergencyHead,                   // Position of the oldest appeal that may still be open
+    PendingExpensesHead,             // Position of the oldest undecided entry in the review queue
+    FeederMatchingPools(u64),        // Pools that may still match donations to the feeder
+    ActiveSponsorship(Address, u64), // (donor, dog_id) -> live sponsorship id
+    DonorSummary(Address),
+    DonorFeeder(Address, u64),
+    DonorDog(Address, u64),
+    Audit(AuditKey),
+    Vet(VetKey),
+    FeederLedger(u64),
+    IndexLen(IndexKey),
+    IndexBucket(IndexKey, u64),
 }
 
 #[contract]
@@ -123,72 +931,350 @@ pub struct PawChainContract;
 #[contractimpl]
 impl PawChainContract {
     // Initialize contract with admin address and token
-    pub fn initialize(env: Env, admin: Address, token: Address) {
+    pub fn initialize(env: Env, admin: Address, token: Address) -> Result<(), PawChainError> {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            return Err(PawChainError::AlreadyInitialized);
         }
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::TokenAddress, &token);
+        env.storage().instance().set(
+            &DataKey::Config(ConfigKey::AllowedTokens),
+            &Vec::from_array(&env, [token]),
+        );
         env.storage().instance().set(&DataKey::DogCount, &0u64);
         env.storage().instance().set(&DataKey::FeederCount, &0u64);
         env.storage().instance().set(&DataKey::DonationCount, &0u64);
         env.storage().instance().set(&DataKey::ExpenseCount, &0u64);
-        env.storage().instance().set(&DataKey::TreatmentCount, &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::TreatmentCount, &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Self::extend_instance_ttl(&env);
+        Ok(())
     }
 
-    // ========== DOG MANAGEMENT ==========
+    // ========== ADMINISTRATION ==========
 
-    // Register a new dog
-    pub fn register_dog(
+    // Nominate a new admin; takes effect once they call `accept_admin` (admin only)
+    pub fn propose_admin(
         env: Env,
-        feeder: Address,
-        name: String,
-        age: u32,
-        breed: String,
-        location: String,
-        health_status: String,
-        sickness: String,
-    ) -> u64 {
-        feeder.require_auth();
+        admin: Address,
+        new_admin: Address,
+    ) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
 
-        // Get feeder_id
-        let feeder_id = Self::get_feeder_id_by_address(env.clone(), feeder.clone());
-        if feeder_id == 0 {
-            panic!("Feeder not registered");
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &new_admin);
+
+        AdminProposed {
+            admin,
+            proposed_admin: new_admin,
         }
+        .publish(&env);
+        Ok(())
+    }
 
-        let mut dog_count: u64 = env
+    // Complete an admin handover; must be signed by the proposed admin
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), PawChainError> {
+        new_admin.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let pending: Address = env
             .storage()
             .instance()
-            .get(&DataKey::DogCount)
-            .unwrap_or(0);
-        dog_count += 1;
+            .get(&DataKey::PendingAdmin)
+            .ok_or(PawChainError::NoPendingAdmin)?;
+        if pending != new_admin {
+            return Err(PawChainError::Unauthorized);
+        }
 
-        let dog = DogProfile {
-            dog_id: dog_count,
-            name,
-            age,
-            breed,
-            location,
+        let previous_admin = Self::get_admin(env.clone())?;
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminChanged {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // Get the current admin
+    pub fn get_admin(env: Env) -> Result<Address, PawChainError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(PawChainError::NotInitialized)
+    }
+
+    // Allow donations and expenses in `token` (admin only)
+    pub fn allow_token(env: Env, admin: Address, token: Address) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut tokens = Self::get_allowed_tokens(env.clone());
+        if !tokens.contains(&token) {
+            tokens.push_back(token.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::Config(ConfigKey::AllowedTokens), &tokens);
+        }
+
+        TokenAllowlistUpdated {
+            token,
+            allowed: true,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // Stop accepting new donations and expenses in `token` (admin only).
+    // Existing escrow in the token can still be released.
+    pub fn disallow_token(env: Env, admin: Address, token: Address) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut tokens = Self::get_allowed_tokens(env.clone());
+        let index = tokens
+            .first_index_of(&token)
+            .ok_or(PawChainError::TokenNotAllowed)?;
+        tokens.remove(index);
+        env.storage()
+            .instance()
+            .set(&DataKey::Config(ConfigKey::AllowedTokens), &tokens);
+
+        TokenAllowlistUpdated {
+            token,
+            allowed: false,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // Set the platform fee taken from donations and the treasury receiving it (admin only)
+    pub fn set_fee_config(
+        env: Env,
+        admin: Address,
+        fee_bps: u32,
+        treasury: Address,
+    ) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        if fee_bps > MAX_FEE_BPS {
+            return Err(PawChainError::FeeTooHigh);
+        }
+        let config = FeeConfig { fee_bps, treasury };
+        env.storage()
+            .instance()
+            .set(&DataKey::Config(ConfigKey::FeeConfig), &config);
+
+        FeeConfigUpdated { admin, config }.publish(&env);
+        Ok(())
+    }
+
+    // Get the platform fee config, if one has been set
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config(ConfigKey::FeeConfig))
+    }
+
+    // Get the fees collected so far, per token
+    pub fn get_fee_totals(env: Env) -> Map<Address, i128> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config(ConfigKey::FeeTotals))
+            .unwrap_or(Map::new(&env))
+    }
+
+    // Register a custom expense category and the ActivityStats counter it
+    // increments, if any (admin only). Mappings are permanent so rejected expenses
+    // always reverse the counter they incremented.
+    pub fn register_expense_category(
+        env: Env,
+        admin: Address,
+        name: String,
+        counter: Option<ActivityCounter>,
+    ) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut categories = Self::get_custom_categories(env.clone());
+        if categories.contains_key(name.clone()) {
+            return Err(PawChainError::CategoryAlreadyRegistered);
+        }
+        categories.set(name.clone(), counter);
+        env.storage()
+            .instance()
+            .set(&DataKey::Config(ConfigKey::CustomCategories), &categories);
+
+        CategoryRegistered { name, counter }.publish(&env);
+        Ok(())
+    }
+
+    // Get the registered custom expense categories and their counters
+    pub fn get_custom_categories(env: Env) -> Map<String, Option<ActivityCounter>> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config(ConfigKey::CustomCategories))
+            .unwrap_or(Map::new(&env))
+    }
+
+    // Get the tokens currently accepted for donations and expenses
+    pub fn get_allowed_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config(ConfigKey::AllowedTokens))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Replace the contract code, keeping storage and address (admin only)
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        ContractUpgraded {
+            admin,
+            new_wasm_hash,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // Rewrite stored records into the current schema after an upgrade (admin only).
+    // Processes at most `limit` records per call; returns the schema version reached,
+    // so call repeatedly until it equals `SCHEMA_VERSION`.
+    pub fn migrate(env: Env, admin: Address, limit: u32) -> Result<u32, PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let version = Self::get_schema_version(env.clone());
+        if version == SCHEMA_VERSION {
+            return Err(PawChainError::SchemaUpToDate);
+        }
+
+        let cursor: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MigrationCursor)
+            .unwrap_or(1);
+        match Self::migrate_step(&env, version, cursor, limit)? {
+            Some(next_cursor) => {
+                env.storage()
+                    .instance()
+                    .set(&DataKey::MigrationCursor, &next_cursor);
+                Ok(version)
+            }
+            None => {
+                env.storage().instance().remove(&DataKey::MigrationCursor);
+                env.storage()
+                    .instance()
+                    .set(&DataKey::SchemaVersion, &(version + 1));
+                SchemaMigrated {
+                    from_version: version,
+                    to_version: version + 1,
+                }
+                .publish(&env);
+                Ok(version + 1)
+            }
+        }
+    }
+
+    // Get the storage schema version
+    pub fn get_schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(0)
+    }
+
+    // Bump the TTL of the contract instance and the given persistent records (admin only)
+    pub fn extend_ttl(env: Env, admin: Address, keys: Vec<DataKey>) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        for key in keys.iter() {
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().extend_ttl(
+                    &key,
+                    PERSISTENT_LIFETIME_THRESHOLD,
+                    PERSISTENT_BUMP_AMOUNT,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    // ========== DOG MANAGEMENT ==========
+
+    // Register a new dog
+    pub fn register_dog(
+        env: Env,
+        feeder: Address,
+        name: String,
+        age: u32,
+        breed: String,
+        location: String,
+        health_status: String,
+        sickness: String,
+    ) -> Result<u64, PawChainError> {
+        feeder.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        // Get feeder_id
+        let feeder_id = Self::get_feeder_id_by_address(&env, &feeder)?;
+
+        let mut dog_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DogCount)
+            .unwrap_or(0);
+        dog_count += 1;
+
+        let dog = DogProfile {
+            dog_id: dog_count,
+            name,
+            age,
+            breed,
+            location,
             health_status,
             sickness,
             feeder_id,
             registered_date: env.ledger().timestamp(),
             last_updated: env.ledger().timestamp(),
             is_active: true,
+            departed_at: None,
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Dog(dog_count), &dog);
-        env.storage()
-            .instance()
-            .set(&DataKey::DogCount, &dog_count);
+        Self::write_persistent(&env, &DataKey::Dog(dog_count), &dog);
+        env.storage().instance().set(&DataKey::DogCount, &dog_count);
+
+        Self::index_append(&env, IndexKey::FeederDogs(feeder_id), dog_count);
 
         // Update stats
-        Self::increment_stat(env, feeder_id, symbol_short!("rescued"));
+        Self::increment_stat(&env, feeder_id, ActivityCounter::Rescued)?;
+
+        DogRegistered {
+            feeder_id,
+            dog_id: dog_count,
+            dog,
+        }
+        .publish(&env);
 
-        dog_count
+        Ok(dog_count)
     }
 
     // Update dog health status
@@ -198,33 +1284,268 @@ impl PawChainContract {
         dog_id: u64,
         health_status: String,
         sickness: String,
-    ) {
+    ) -> Result<(), PawChainError> {
         feeder.require_auth();
+        Self::extend_instance_ttl(&env);
 
-        let mut dog: DogProfile = env
-            .storage()
-            .instance()
-            .get(&DataKey::Dog(dog_id))
-            .unwrap();
+        let mut dog = Self::load_dog(&env, dog_id)?;
 
-        let feeder_id = Self::get_feeder_id_by_address(env.clone(), feeder);
+        let feeder_id = Self::get_feeder_id_by_address(&env, &feeder)?;
         if dog.feeder_id != feeder_id {
-            panic!("Unauthorized");
+            return Err(PawChainError::Unauthorized);
         }
 
+        let health_changed = dog.health_status != health_status;
         dog.health_status = health_status;
         dog.sickness = sickness;
         dog.last_updated = env.ledger().timestamp();
 
-        env.storage().instance().set(&DataKey::Dog(dog_id), &dog);
+        Self::write_persistent(&env, &DataKey::Dog(dog_id), &dog);
+        if health_changed {
+            if let Some(appeal) = Self::get_dog_emergency(env.clone(), dog_id) {
+                Self::close_emergency(&env, appeal, EmergencyStatus::HealthChanged);
+            }
+        }
+
+        DogHealthUpdated {
+            feeder_id,
+            dog_id,
+            dog,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // Mark a dog as adopted or deceased (managing feeder only). Ends its sponsorships.
+    pub fn deactivate_dog(
+        env: Env,
+        feeder: Address,
+        dog_id: u64,
+        reason: DogExitReason,
+    ) -> Result<(), PawChainError> {
+        feeder.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let mut dog = Self::load_dog(&env, dog_id)?;
+        let feeder_id = Self::get_feeder_id_by_address(&env, &feeder)?;
+        if dog.feeder_id != feeder_id {
+            return Err(PawChainError::Unauthorized);
+        }
+        if !dog.is_active {
+            return Err(PawChainError::DogInactive);
+        }
+
+        dog.is_active = false;
+        dog.last_updated = env.ledger().timestamp();
+        dog.departed_at = Some(dog.last_updated);
+        Self::write_persistent(&env, &DataKey::Dog(dog_id), &dog);
+        if reason == DogExitReason::Adopted {
+            Self::increment_stat(&env, feeder_id, ActivityCounter::Adopted)?;
+        }
+        if let Some(appeal) = Self::get_dog_emergency(env.clone(), dog_id) {
+            Self::close_emergency(&env, appeal, EmergencyStatus::DogDeparted);
+        }
+        Self::release_dog_fund(&env, &dog);
+
+        DogDeactivated {
+            feeder_id,
+            dog_id,
+            reason,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // Get the unspent donations earmarked for a dog, per token
+    pub fn get_dog_fund(env: Env, dog_id: u64) -> Result<Map<Address, i128>, PawChainError> {
+        Self::load_dog(&env, dog_id)?;
+        Ok(Self::load_fund(&env, &DataKey::DogFund(dog_id)))
+    }
+
+    // Get the earmarked funds a feeder's adopted or deceased dogs left behind, per token
+    pub fn get_general_fund(env: Env, feeder_id: u64) -> Result<Map<Address, i128>, PawChainError> {
+        Self::load_feeder(&env, feeder_id)?;
+        Ok(Self::load_fund(&env, &DataKey::GeneralFund(feeder_id)))
     }
 
     // Get dog profile
-    pub fn get_dog(env: Env, dog_id: u64) -> DogProfile {
+    pub fn get_dog(env: Env, dog_id: u64) -> Result<DogProfile, PawChainError> {
+        Self::load_dog(&env, dog_id)
+    }
+
+    // List dogs with ids in [start, start + limit)
+    pub fn list_dogs(env: Env, start: u64, limit: u32) -> Vec<DogProfile> {
+        Self::list_records(&env, &DataKey::DogCount, DataKey::Dog, start, limit)
+    }
+
+    // List a dog's treatments, entries [start, start + limit) of its history
+    pub fn get_dog_treatments(
+        env: Env,
+        dog_id: u64,
+        start: u64,
+        limit: u32,
+    ) -> Result<Vec<TreatmentRecord>, PawChainError> {
+        Self::load_dog(&env, dog_id)?;
+        let ids = Self::index_range(&env, &IndexKey::DogTreatments(dog_id), start, limit);
+        Ok(Self::load_records(&env, &ids, DataKey::Treatment))
+    }
+
+    // List the expenses that listed a dog in dogs_affected, entries [start, start + limit)
+    pub fn get_dog_expenses(
+        env: Env,
+        dog_id: u64,
+        start: u64,
+        limit: u32,
+    ) -> Result<Vec<ExpenseRecord>, PawChainError> {
+        Self::load_dog(&env, dog_id)?;
+        let ids = Self::index_range(&env, &IndexKey::DogExpenses(dog_id), start, limit);
+        Ok(Self::load_records(&env, &ids, DataKey::Expense))
+    }
+
+    // Get a dog's profile together with its latest treatments, linked expenses and spend
+    pub fn get_dog_dossier(env: Env, dog_id: u64) -> Result<DogDossier, PawChainError> {
+        let profile = Self::load_dog(&env, dog_id)?;
+
+        let treatment_index = IndexKey::DogTreatments(dog_id);
+        let total_treatments = Self::index_len(&env, &treatment_index);
+        let ids = Self::index_range(
+            &env,
+            &treatment_index,
+            total_treatments.saturating_sub(MAX_PAGE_SIZE as u64),
+            MAX_PAGE_SIZE,
+        );
+        let treatments = Self::load_records(&env, &ids, DataKey::Treatment);
+
+        let expense_index = IndexKey::DogExpenses(dog_id);
+        let total_expenses = Self::index_len(&env, &expense_index);
+        let ids = Self::index_range(
+            &env,
+            &expense_index,
+            total_expenses.saturating_sub(MAX_PAGE_SIZE as u64),
+            MAX_PAGE_SIZE,
+        );
+        let expenses = Self::load_records(&env, &ids, DataKey::Expense);
+
+        Ok(DogDossier {
+            profile,
+            treatments,
+            expenses,
+            total_treatments,
+            total_expenses,
+            spend: Self::load_dog_spend(&env, dog_id),
+        })
+    }
+
+    // ========== EMERGENCY APPEALS ==========
+
+    // Open a time-boxed appeal for a dog whose health is "Critical" (managing feeder
+    // only). "Emergency" donations naming the dog count towards the target.
+    pub fn open_emergency(
+        env: Env,
+        feeder: Address,
+        dog_id: u64,
+        token: Address,
+        target: i128,
+        deadline: u64,
+    ) -> Result<u64, PawChainError> {
+        feeder.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let dog = Self::load_dog(&env, dog_id)?;
+        let feeder_id = Self::get_feeder_id_by_address(&env, &feeder)?;
+        if dog.feeder_id != feeder_id {
+            return Err(PawChainError::Unauthorized);
+        }
+        if !dog.is_active {
+            return Err(PawChainError::DogInactive);
+        }
+        if dog.health_status != String::from_str(&env, "Critical") {
+            return Err(PawChainError::DogNotCritical);
+        }
+        if target <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+        let now = env.ledger().timestamp();
+        if deadline <= now {
+            return Err(PawChainError::InvalidDeadline);
+        }
+        Self::require_allowed_token(&env, &token)?;
+        if let Some(appeal) = Self::get_dog_emergency(env.clone(), dog_id) {
+            if now < appeal.deadline {
+                return Err(PawChainError::EmergencyAlreadyOpen);
+            }
+            Self::close_emergency(&env, appeal, EmergencyStatus::Expired);
+        }
+
+        let mut appeal_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::EmergencyCount)
+            .unwrap_or(0);
+        appeal_count += 1;
+
+        let appeal = EmergencyAppeal {
+            appeal_id: appeal_count,
+            dog_id,
+            feeder_id,
+            token,
+            target,
+            raised: 0,
+            opened_at: now,
+            deadline,
+            closed_at: None,
+            status: EmergencyStatus::Open,
+        };
+
+        Self::write_persistent(&env, &DataKey::Emergency(appeal_count), &appeal);
         env.storage()
             .instance()
-            .get(&DataKey::Dog(dog_id))
-            .unwrap()
+            .set(&DataKey::EmergencyCount, &appeal_count);
+        Self::write_persistent(&env, &DataKey::DogEmergency(dog_id), &appeal_count);
+        Self::index_append(&env, IndexKey::Emergencies, appeal_count);
+        Self::advance_emergency_head(&env);
+
+        EmergencyOpened {
+            feeder_id,
+            dog_id,
+            appeal,
+        }
+        .publish(&env);
+
+        Ok(appeal_count)
+    }
+
+    // Get emergency appeal details
+    pub fn get_emergency(env: Env, appeal_id: u64) -> Result<EmergencyAppeal, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Emergency(appeal_id))
+            .ok_or(PawChainError::EmergencyNotFound)
+    }
+
+    // Get the appeal currently open for a dog, if any
+    pub fn get_dog_emergency(env: Env, dog_id: u64) -> Option<EmergencyAppeal> {
+        let appeal_id: u64 = Self::read_persistent(&env, &DataKey::DogEmergency(dog_id))?;
+        Self::get_emergency(env, appeal_id).ok()
+    }
+
+    // List open appeals whose deadline has not passed, oldest first, from entries
+    // [start, start + limit) past the head. Appeals closed or expired since are
+    // skipped, so a page can come back short.
+    pub fn list_active_emergencies(env: Env, start: u64, limit: u32) -> Vec<EmergencyAppeal> {
+        let ids = Self::index_range(
+            &env,
+            &IndexKey::Emergencies,
+            Self::emergency_head(&env).saturating_add(start),
+            limit,
+        );
+        let mut active = Vec::new(&env);
+        for appeal in Self::load_records::<EmergencyAppeal>(&env, &ids, DataKey::Emergency) {
+            if Self::is_active_emergency(&env, &appeal) {
+                active.push_back(appeal);
+            }
+        }
+        active
     }
 
     // ========== FEEDER/NGO MANAGEMENT ==========
@@ -238,8 +1559,15 @@ impl PawChainContract {
         location: String,
         registration_number: String,
         contact_info: String,
-    ) -> u64 {
+    ) -> Result<u64, PawChainError> {
         wallet_address.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        // One profile per wallet
+        let address_key = DataKey::FeederByAddress(wallet_address.clone());
+        if env.storage().persistent().has(&address_key) {
+            return Err(PawChainError::FeederAlreadyRegistered);
+        }
 
         let mut feeder_count: u64 = env
             .storage()
@@ -258,16 +1586,15 @@ impl PawChainContract {
             contact_info,
             registered_date: env.ledger().timestamp(),
             is_verified: false,
-            total_received: 0,
-            total_spent: 0,
+            total_received: Map::new(&env),
+            total_spent: Map::new(&env),
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Feeder(feeder_count), &feeder);
+        Self::write_persistent(&env, &DataKey::Feeder(feeder_count), &feeder);
         env.storage()
             .instance()
             .set(&DataKey::FeederCount, &feeder_count);
+        Self::write_persistent(&env, &address_key, &feeder_count);
 
         // Initialize stats
         let stats = ActivityStats {
@@ -281,80 +1608,2359 @@ impl PawChainContract {
             dogs_adopted: 0,
             last_updated: env.ledger().timestamp(),
         };
-        env.storage()
-            .instance()
-            .set(&DataKey::FeederStats(feeder_count), &stats);
+        Self::write_persistent(&env, &DataKey::FeederStats(feeder_count), &stats);
+
+        FeederRegistered {
+            feeder_id: feeder_count,
+            wallet_address,
+            feeder,
+        }
+        .publish(&env);
+
+        Ok(feeder_count)
+    }
+
+    // Verify a feeder (admin only)
+    pub fn verify_feeder(env: Env, admin: Address, feeder_id: u64) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut feeder = Self::load_feeder(&env, feeder_id)?;
+        feeder.is_verified = true;
+        Self::write_persistent(&env, &DataKey::Feeder(feeder_id), &feeder);
+
+        FeederVerified {
+            feeder_id,
+            admin,
+            feeder,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // Reconcile a feeder's donations in `token` against the spend charged to them
+    pub fn get_feeder_balance(
+        env: Env,
+        feeder_id: u64,
+        token: Address,
+    ) -> Result<FeederBalance, PawChainError> {
+        let feeder = Self::load_feeder(&env, feeder_id)?;
+        let ledger = Self::load_feeder_ledger(&env, feeder_id);
+        let received = feeder.total_received.get(token.clone()).unwrap_or(0);
+        let pawchain_spent = ledger.pawchain_spent.get(token.clone()).unwrap_or(0);
+        Ok(FeederBalance {
+            feeder_id,
+            escrowed: Self::load_escrow(&env, feeder_id)
+                .get(token.clone())
+                .unwrap_or(0),
+            token: token.clone(),
+            received,
+            pawchain_spent,
+            external_spent: ledger.external_spent.get(token).unwrap_or(0),
+            available: received - pawchain_spent,
+        })
+    }
+
+    // Get feeder profile
+    pub fn get_feeder(env: Env, feeder_id: u64) -> Result<FeederProfile, PawChainError> {
+        Self::load_feeder(&env, feeder_id)
+    }
+
+    // List feeders with ids in [start, start + limit)
+    pub fn list_feeders(env: Env, start: u64, limit: u32) -> Vec<FeederProfile> {
+        Self::list_records(&env, &DataKey::FeederCount, DataKey::Feeder, start, limit)
+    }
+
+    // List the dogs managed by a feeder, entries [start, start + limit) of its index
+    pub fn get_feeder_dogs(
+        env: Env,
+        feeder_id: u64,
+        start: u64,
+        limit: u32,
+    ) -> Result<Vec<DogProfile>, PawChainError> {
+        Self::load_feeder(&env, feeder_id)?;
+        let ids = Self::index_range(&env, &IndexKey::FeederDogs(feeder_id), start, limit);
+        Ok(Self::load_records(&env, &ids, DataKey::Dog))
+    }
+
+    // List the donations received by a feeder, entries [start, start + limit) of its index
+    pub fn get_feeder_donations(
+        env: Env,
+        feeder_id: u64,
+        start: u64,
+        limit: u32,
+    ) -> Result<Vec<Donation>, PawChainError> {
+        Self::load_feeder(&env, feeder_id)?;
+        let ids = Self::index_range(&env, &IndexKey::FeederDonations(feeder_id), start, limit);
+        Ok(Self::load_records(&env, &ids, DataKey::Donation))
+    }
+
+    // List the expenses of a feeder, entries [start, start + limit) of its index
+    pub fn get_feeder_expenses(
+        env: Env,
+        feeder_id: u64,
+        start: u64,
+        limit: u32,
+    ) -> Result<Vec<ExpenseRecord>, PawChainError> {
+        Self::load_feeder(&env, feeder_id)?;
+        let ids = Self::index_range(&env, &IndexKey::FeederExpenses(feeder_id), start, limit);
+        Ok(Self::load_records(&env, &ids, DataKey::Expense))
+    }
+
+    // Get feeder statistics
+    pub fn get_feeder_stats(env: Env, feeder_id: u64) -> Result<ActivityStats, PawChainError> {
+        Self::read_persistent(&env, &DataKey::FeederStats(feeder_id))
+            .ok_or(PawChainError::FeederNotFound)
+    }
+
+    // ========== DONATION MANAGEMENT ==========
+
+    // Record a donation in any allowlisted token
+    pub fn donate(
+        env: Env,
+        donor: Address,
+        feeder_id: u64,
+        token: Address,
+        amount: i128,
+        purpose: String,
+        dog_id: Option<u64>,
+    ) -> Result<u64, PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+
+        Self::require_allowed_token(&env, &token)?;
+
+        // Get feeder wallet
+        let feeder = Self::load_feeder(&env, feeder_id)?;
+        if let Some(dog_id) = dog_id {
+            Self::load_feeder_dog(&env, dog_id, feeder_id)?;
+        }
+
+        // Transfer tokens from donor to feeder, less the platform fee
+        let token_client = token::Client::new(&env, &token);
+        let fee = Self::take_fee(&env, &token, amount, |treasury, fee| {
+            token_client.transfer(&donor, treasury, fee)
+        });
+        token_client.transfer(&donor, &feeder.wallet_address, &(amount - fee));
+
+        let donation = Self::record_donation(
+            &env, donor, feeder, token, amount, fee, purpose, dog_id, false, None, None,
+        );
+        Self::match_donation(&env, &donation)?;
+        Ok(donation.donation_id)
+    }
+
+    // Split one donation across several (feeder_id, dog_id, share_bps) recipients.
+    // Shares must sum to 10000; rounding dust goes to the last recipient. Each
+    // recipient gets its own Donation, linked by the returned batch id.
+    pub fn donate_split(
+        env: Env,
+        donor: Address,
+        recipients: Vec<(u64, Option<u64>, u32)>,
+        token: Address,
+        amount: i128,
+        purpose: String,
+    ) -> Result<u64, PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+        if recipients.is_empty() || recipients.len() > MAX_SPLIT_RECIPIENTS {
+            return Err(PawChainError::InvalidSplit);
+        }
+        Self::require_allowed_token(&env, &token)?;
+
+        // Validate every recipient before moving any tokens
+        let mut amounts = Vec::new(&env);
+        let mut total_bps: u32 = 0;
+        let mut allocated: i128 = 0;
+        for (i, (feeder_id, dog_id, share_bps)) in recipients.iter().enumerate() {
+            if share_bps == 0 {
+                return Err(PawChainError::InvalidSplit);
+            }
+            total_bps = total_bps
+                .checked_add(share_bps)
+                .filter(|total| *total <= BPS_DENOMINATOR)
+                .ok_or(PawChainError::InvalidSplit)?;
+            Self::load_feeder(&env, feeder_id)?;
+            if let Some(dog_id) = dog_id {
+                Self::load_feeder_dog(&env, dog_id, feeder_id)?;
+            }
+            let share = if i as u32 == recipients.len() - 1 {
+                amount - allocated
+            } else {
+                amount
+                    .checked_mul(share_bps as i128)
+                    .ok_or(PawChainError::InvalidAmount)?
+                    / BPS_DENOMINATOR as i128
+            };
+            if share <= 0 {
+                return Err(PawChainError::InvalidAmount);
+            }
+            allocated += share;
+            amounts.push_back(share);
+        }
+        if total_bps != BPS_DENOMINATOR {
+            return Err(PawChainError::InvalidSplit);
+        }
+
+        let batch_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::BatchCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::BatchCount, &batch_id);
+
+        let token_client = token::Client::new(&env, &token);
+        for (i, (feeder_id, dog_id, _)) in recipients.iter().enumerate() {
+            // Reloaded per share, so a feeder listed twice is credited with both
+            let feeder = Self::load_feeder(&env, feeder_id)?;
+            let share = amounts.get_unchecked(i as u32);
+            let fee = Self::take_fee(&env, &token, share, |treasury, fee| {
+                token_client.transfer(&donor, treasury, fee)
+            });
+            token_client.transfer(&donor, &feeder.wallet_address, &(share - fee));
+            let donation = Self::record_donation(
+                &env,
+                donor.clone(),
+                feeder,
+                token.clone(),
+                share,
+                fee,
+                purpose.clone(),
+                dog_id,
+                false,
+                Some(batch_id),
+                None,
+            );
+            Self::index_append(
+                &env,
+                IndexKey::BatchDonations(batch_id),
+                donation.donation_id,
+            );
+        }
+        Ok(batch_id)
+    }
+
+    // Get the donations made by one `donate_split` call
+    pub fn get_batch_donations(env: Env, batch_id: u64) -> Vec<Donation> {
+        let ids = Self::index_range(
+            &env,
+            &IndexKey::BatchDonations(batch_id),
+            0,
+            MAX_SPLIT_RECIPIENTS,
+        );
+        Self::load_records(&env, &ids, DataKey::Donation)
+    }
+
+    // Donate into escrow: tokens are held by the contract and released to the
+    // feeder as their expenses are verified
+    pub fn donate_escrowed(
+        env: Env,
+        donor: Address,
+        feeder_id: u64,
+        token: Address,
+        amount: i128,
+        purpose: String,
+        dog_id: Option<u64>,
+    ) -> Result<u64, PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+
+        Self::require_allowed_token(&env, &token)?;
+        let feeder = Self::load_feeder(&env, feeder_id)?;
+        if let Some(dog_id) = dog_id {
+            Self::load_feeder_dog(&env, dog_id, feeder_id)?;
+        }
+
+        // Transfer tokens from donor to the contract, less the platform fee
+        let token_client = token::Client::new(&env, &token);
+        let fee = Self::take_fee(&env, &token, amount, |treasury, fee| {
+            token_client.transfer(&donor, treasury, fee)
+        });
+        token_client.transfer(&donor, env.current_contract_address(), &(amount - fee));
+
+        let mut escrow = Self::load_escrow(&env, feeder_id);
+        Self::add_amount(&mut escrow, &token, amount - fee);
+        Self::write_persistent(&env, &DataKey::EscrowBalance(feeder_id), &escrow);
+
+        let donation = Self::record_donation(
+            &env, donor, feeder, token, amount, fee, purpose, dog_id, true, None, None,
+        );
+        Ok(donation.donation_id)
+    }
+
+    // Get the escrowed funds in `token` still held for a feeder
+    pub fn get_escrow_balance(
+        env: Env,
+        feeder_id: u64,
+        token: Address,
+    ) -> Result<i128, PawChainError> {
+        Self::load_feeder(&env, feeder_id)?;
+        Ok(Self::load_escrow(&env, feeder_id).get(token).unwrap_or(0))
+    }
+
+    // Get donation details
+    pub fn get_donation(env: Env, donation_id: u64) -> Result<Donation, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Donation(donation_id))
+            .ok_or(PawChainError::DonationNotFound)
+    }
+
+    // List donations with ids in [start, start + limit)
+    pub fn list_donations(env: Env, start: u64, limit: u32) -> Vec<Donation> {
+        Self::list_records(
+            &env,
+            &DataKey::DonationCount,
+            DataKey::Donation,
+            start,
+            limit,
+        )
+    }
+
+    // List a donor's donations, entries [start, start + limit) of their history
+    pub fn get_donor_donations(env: Env, donor: Address, start: u64, limit: u32) -> Vec<Donation> {
+        let ids = Self::index_range(&env, &IndexKey::DonorDonations(donor), start, limit);
+        Self::load_records(&env, &ids, DataKey::Donation)
+    }
+
+    // Get a donor's lifetime giving totals
+    pub fn get_donor_summary(env: Env, donor: Address) -> DonorSummary {
+        Self::read_persistent(&env, &DataKey::DonorSummary(donor.clone())).unwrap_or(DonorSummary {
+            donor,
+            total_given: Map::new(&env),
+            donation_count: 0,
+            feeders_supported: 0,
+            dogs_supported: 0,
+            last_donation: 0,
+        })
+    }
+
+    // ========== CAMPAIGNS ==========
+
+    // Open a fundraising campaign with a goal and deadline
+    pub fn create_campaign(
+        env: Env,
+        feeder: Address,
+        title: String,
+        dog_id: Option<u64>,
+        goal: i128,
+        deadline: u64,
+    ) -> Result<u64, PawChainError> {
+        feeder.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if goal <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+        if deadline <= env.ledger().timestamp() {
+            return Err(PawChainError::InvalidDeadline);
+        }
+
+        let feeder_id = Self::get_feeder_id_by_address(&env, &feeder)?;
+        if let Some(dog_id) = dog_id {
+            Self::load_feeder_dog(&env, dog_id, feeder_id)?;
+        }
+        Self::default_token_client(&env)?;
+
+        let mut campaign_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignCount)
+            .unwrap_or(0);
+        campaign_count += 1;
+
+        let campaign = Campaign {
+            campaign_id: campaign_count,
+            feeder_id,
+            dog_id,
+            title,
+            goal,
+            raised: 0,
+            deadline,
+            created_at: env.ledger().timestamp(),
+            status: CampaignStatus::Active,
+            unrecorded: 0,
+            unrecorded_fee: 0,
+        };
+
+        Self::write_persistent(&env, &DataKey::Campaign(campaign_count), &campaign);
+        env.storage()
+            .instance()
+            .set(&DataKey::CampaignCount, &campaign_count);
+
+        CampaignCreated {
+            feeder_id,
+            campaign_id: campaign_count,
+            campaign,
+        }
+        .publish(&env);
+
+        Ok(campaign_count)
+    }
+
+    // Contribute to an active campaign; funds are held by the contract until it is finalized.
+    // Returns the donor's total contribution to the campaign.
+    pub fn donate_to_campaign(
+        env: Env,
+        donor: Address,
+        campaign_id: u64,
+        amount: i128,
+    ) -> Result<i128, PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id)?;
+        if campaign.status != CampaignStatus::Active
+            || env.ledger().timestamp() >= campaign.deadline
+        {
+            return Err(PawChainError::CampaignClosed);
+        }
+
+        Self::default_token_client(&env)?.transfer(&donor, env.current_contract_address(), &amount);
+
+        campaign.raised += amount;
+        Self::write_persistent(&env, &DataKey::Campaign(campaign_id), &campaign);
+
+        let contribution_key = DataKey::CampaignContribution(campaign_id, donor.clone());
+        let contribution: i128 = Self::read_persistent(&env, &contribution_key).unwrap_or(0);
+        Self::write_persistent(&env, &contribution_key, &(contribution + amount));
+        if contribution == 0 {
+            Self::index_append(
+                &env,
+                IndexKey::CampaignContributors(campaign_id),
+                donor.clone(),
+            );
+        }
+
+        CampaignContributed {
+            campaign_id,
+            donor,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(contribution + amount)
+    }
+
+    // Close a campaign once its deadline has passed: pays the feeder if the goal
+    // was met, otherwise opens refunds. Callable by anyone.
+    pub fn finalize_campaign(env: Env, campaign_id: u64) -> Result<Campaign, PawChainError> {
+        Self::extend_instance_ttl(&env);
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id)?;
+        if campaign.status != CampaignStatus::Active {
+            return Err(PawChainError::CampaignClosed);
+        }
+        if env.ledger().timestamp() < campaign.deadline {
+            return Err(PawChainError::CampaignStillActive);
+        }
+        Self::settle_campaign(&env, campaign)
+    }
+
+    // Withdraw a contribution from a campaign that missed its goal. Settles the
+    // campaign first if its deadline has passed but nobody has finalized it yet.
+    pub fn claim_refund(env: Env, donor: Address, campaign_id: u64) -> Result<i128, PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id)?;
+        if campaign.status == CampaignStatus::Active {
+            if env.ledger().timestamp() < campaign.deadline {
+                return Err(PawChainError::CampaignStillActive);
+            }
+            campaign = Self::settle_campaign(&env, campaign)?;
+        }
+        if campaign.status != CampaignStatus::Failed {
+            return Err(PawChainError::CampaignClosed);
+        }
+
+        let contribution_key = DataKey::CampaignContribution(campaign_id, donor.clone());
+        let amount: i128 = Self::read_persistent(&env, &contribution_key).unwrap_or(0);
+        if amount <= 0 {
+            return Err(PawChainError::NothingToRefund);
+        }
+
+        Self::write_persistent(&env, &contribution_key, &0i128);
+        Self::token_client(&env)?.transfer(&env.current_contract_address(), &donor, &amount);
+
+        CampaignRefunded {
+            campaign_id,
+            donor,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    // Record contributors [start, start + limit) of a paid-out campaign as donations
+    // to its feeder, each charged its share of the payout fee. Contributors already
+    // recorded are skipped. Callable by anyone. Returns the donations recorded.
+    pub fn record_campaign_payouts(
+        env: Env,
+        campaign_id: u64,
+        start: u64,
+        limit: u32,
+    ) -> Result<u32, PawChainError> {
+        Self::extend_instance_ttl(&env);
+
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id)?;
+        match campaign.status {
+            CampaignStatus::Successful => {}
+            CampaignStatus::Active => return Err(PawChainError::CampaignStillActive),
+            CampaignStatus::Failed => return Err(PawChainError::CampaignClosed),
+        }
+        let token = Self::token_client(&env)?.address;
+
+        let donors: Vec<Address> = Self::index_range(
+            &env,
+            &IndexKey::CampaignContributors(campaign_id),
+            start,
+            limit,
+        );
+        let mut recorded = 0;
+        for donor in donors.iter() {
+            let contribution_key = DataKey::CampaignContribution(campaign_id, donor.clone());
+            let amount: i128 = Self::read_persistent(&env, &contribution_key).unwrap_or(0);
+            // Campaigns paid out before v17 have nothing left to record
+            if amount <= 0 || amount > campaign.unrecorded {
+                continue;
+            }
+            // Charging the rest of the fee pro rata to what is left to record
+            // attributes all of it once every contributor has been recorded
+            let fee = campaign.unrecorded_fee * amount / campaign.unrecorded;
+            campaign.unrecorded -= amount;
+            campaign.unrecorded_fee -= fee;
+            Self::write_persistent(&env, &contribution_key, &0i128);
+            Self::record_donation(
+                &env,
+                donor,
+                Self::load_feeder(&env, campaign.feeder_id)?,
+                token.clone(),
+                amount,
+                fee,
+                String::from_str(&env, "Campaign"),
+                campaign.dog_id,
+                false,
+                None,
+                None,
+            );
+            recorded += 1;
+        }
+        if recorded > 0 {
+            Self::write_persistent(&env, &DataKey::Campaign(campaign_id), &campaign);
+        }
+
+        Ok(recorded)
+    }
+
+    // Get campaign details
+    pub fn get_campaign(env: Env, campaign_id: u64) -> Result<Campaign, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Campaign(campaign_id))
+            .ok_or(PawChainError::CampaignNotFound)
+    }
+
+    // List campaigns with ids in [start, start + limit)
+    pub fn list_campaigns(env: Env, start: u64, limit: u32) -> Vec<Campaign> {
+        Self::list_records(
+            &env,
+            &DataKey::CampaignCount,
+            DataKey::Campaign,
+            start,
+            limit,
+        )
+    }
+
+    // Get a donor's outstanding contribution to a campaign: not yet refunded, or not
+    // yet recorded as a donation once the campaign paid out
+    pub fn get_campaign_contribution(env: Env, campaign_id: u64, donor: Address) -> i128 {
+        Self::read_persistent(&env, &DataKey::CampaignContribution(campaign_id, donor)).unwrap_or(0)
+    }
+
+    // ========== MATCHING POOLS ==========
+
+    // Fund a pool that matches `donate` calls to the eligible feeders at `match_bps`
+    // (10000 = 1:1) until `cap` is spent or the pool expires
+    pub fn create_matching_pool(
+        env: Env,
+        sponsor: Address,
+        token: Address,
+        cap: i128,
+        match_bps: u32,
+        eligible_feeders: Vec<u64>,
+        expires_at: u64,
+    ) -> Result<u64, PawChainError> {
+        sponsor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if cap <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+        if match_bps == 0 || eligible_feeders.is_empty() || eligible_feeders.len() > MAX_PAGE_SIZE {
+            return Err(PawChainError::InvalidMatchingPool);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(PawChainError::InvalidDeadline);
+        }
+        Self::require_allowed_token(&env, &token)?;
+        for feeder_id in eligible_feeders.iter() {
+            Self::load_feeder(&env, feeder_id)?;
+        }
+
+        token::Client::new(&env, &token).transfer(&sponsor, env.current_contract_address(), &cap);
+
+        let mut pool_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MatchingPoolCount)
+            .unwrap_or(0);
+        pool_count += 1;
+
+        let pool = MatchingPool {
+            pool_id: pool_count,
+            sponsor: sponsor.clone(),
+            token,
+            cap,
+            remaining: cap,
+            match_bps,
+            eligible_feeders: eligible_feeders.clone(),
+            expires_at,
+            created_at: env.ledger().timestamp(),
+        };
+
+        Self::write_persistent(&env, &DataKey::MatchingPool(pool_count), &pool);
+        env.storage()
+            .instance()
+            .set(&DataKey::MatchingPoolCount, &pool_count);
+        for feeder_id in eligible_feeders.iter() {
+            let key = DataKey::FeederMatchingPools(feeder_id);
+            let mut pools: Vec<u64> = Self::read_persistent(&env, &key).unwrap_or(Vec::new(&env));
+            if !pools.contains(pool_count) {
+                pools.push_back(pool_count);
+                Self::write_persistent(&env, &key, &pools);
+            }
+        }
+
+        MatchingPoolCreated {
+            pool_id: pool_count,
+            sponsor,
+            pool,
+        }
+        .publish(&env);
+
+        Ok(pool_count)
+    }
+
+    // Return a pool's unspent funds to its sponsor once it has expired
+    pub fn reclaim_matching_pool(
+        env: Env,
+        sponsor: Address,
+        pool_id: u64,
+    ) -> Result<i128, PawChainError> {
+        sponsor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let mut pool = Self::get_matching_pool(env.clone(), pool_id)?;
+        if pool.sponsor != sponsor {
+            return Err(PawChainError::Unauthorized);
+        }
+        if env.ledger().timestamp() < pool.expires_at {
+            return Err(PawChainError::MatchingPoolStillActive);
+        }
+        let amount = pool.remaining;
+        if amount <= 0 {
+            return Err(PawChainError::NothingToRefund);
+        }
+
+        pool.remaining = 0;
+        Self::write_persistent(&env, &DataKey::MatchingPool(pool_id), &pool);
+        token::Client::new(&env, &pool.token).transfer(
+            &env.current_contract_address(),
+            &sponsor,
+            &amount,
+        );
+
+        MatchingPoolReclaimed {
+            pool_id,
+            sponsor,
+            amount,
+        }
+        .publish(&env);
+
+        Ok(amount)
+    }
+
+    // Get matching pool details
+    pub fn get_matching_pool(env: Env, pool_id: u64) -> Result<MatchingPool, PawChainError> {
+        Self::read_persistent(&env, &DataKey::MatchingPool(pool_id))
+            .ok_or(PawChainError::MatchingPoolNotFound)
+    }
+
+    // List matching pools with ids in [start, start + limit)
+    pub fn list_matching_pools(env: Env, start: u64, limit: u32) -> Vec<MatchingPool> {
+        Self::list_records(
+            &env,
+            &DataKey::MatchingPoolCount,
+            DataKey::MatchingPool,
+            start,
+            limit,
+        )
+    }
+
+    // ========== SUBSCRIPTIONS ==========
+
+    // Set up a recurring donation. The donor must separately `approve` this contract
+    // on the token for at least `amount` per pull; the first payment is due immediately.
+    pub fn create_subscription(
+        env: Env,
+        donor: Address,
+        feeder_id: u64,
+        amount: i128,
+        interval: u64,
+        dog_id: Option<u64>,
+    ) -> Result<u64, PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+        if interval == 0 {
+            return Err(PawChainError::InvalidInterval);
+        }
+        Self::load_feeder(&env, feeder_id)?;
+        if let Some(dog_id) = dog_id {
+            Self::load_feeder_dog(&env, dog_id, feeder_id)?;
+        }
+        Self::default_token_client(&env)?;
+
+        let mut subscription_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SubscriptionCount)
+            .unwrap_or(0);
+        subscription_count += 1;
+
+        let subscription = Subscription {
+            subscription_id: subscription_count,
+            donor: donor.clone(),
+            feeder_id,
+            dog_id,
+            amount,
+            interval,
+            next_payment_due: env.ledger().timestamp(),
+            payments_made: 0,
+            created_at: env.ledger().timestamp(),
+            is_active: true,
+        };
+
+        Self::write_persistent(
+            &env,
+            &DataKey::Subscription(subscription_count),
+            &subscription,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::SubscriptionCount, &subscription_count);
+
+        SubscriptionCreated {
+            feeder_id,
+            donor,
+            subscription,
+        }
+        .publish(&env);
+
+        Ok(subscription_count)
+    }
+
+    // Pull a due subscription payment from the donor's allowance and record it as a
+    // donation. Callable by anyone, so keepers can run it on schedule.
+    pub fn process_subscription(env: Env, subscription_id: u64) -> Result<u64, PawChainError> {
+        Self::extend_instance_ttl(&env);
+
+        let mut subscription = Self::get_subscription(env.clone(), subscription_id)?;
+        if !subscription.is_active {
+            return Err(PawChainError::SubscriptionInactive);
+        }
+        let now = env.ledger().timestamp();
+        if now < subscription.next_payment_due {
+            return Err(PawChainError::PaymentNotDue);
+        }
+
+        let token_client = Self::default_token_client(&env)?;
+        let contract = env.current_contract_address();
+        if token_client.allowance(&subscription.donor, &contract) < subscription.amount {
+            return Err(PawChainError::InsufficientAllowance);
+        }
+
+        let feeder = Self::load_feeder(&env, subscription.feeder_id)?;
+        let fee = Self::take_fee(
+            &env,
+            &token_client.address,
+            subscription.amount,
+            |treasury, fee| {
+                token_client.transfer_from(&contract, &subscription.donor, treasury, fee)
+            },
+        );
+        token_client.transfer_from(
+            &contract,
+            &subscription.donor,
+            &feeder.wallet_address,
+            &(subscription.amount - fee),
+        );
+
+        // Missed intervals are skipped rather than charged retroactively
+        subscription.next_payment_due += subscription.interval;
+        if subscription.next_payment_due <= now {
+            subscription.next_payment_due = now + subscription.interval;
+        }
+        subscription.payments_made += 1;
+        Self::write_persistent(&env, &DataKey::Subscription(subscription_id), &subscription);
+
+        let donation = Self::record_donation(
+            &env,
+            subscription.donor,
+            feeder,
+            token_client.address.clone(),
+            subscription.amount,
+            fee,
+            String::from_str(&env, "Subscription"),
+            subscription.dog_id,
+            false,
+            None,
+            None,
+        );
+        Ok(donation.donation_id)
+    }
+
+    // Stop a recurring donation (donor only)
+    pub fn cancel_subscription(
+        env: Env,
+        donor: Address,
+        subscription_id: u64,
+    ) -> Result<(), PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let mut subscription = Self::get_subscription(env.clone(), subscription_id)?;
+        if subscription.donor != donor {
+            return Err(PawChainError::Unauthorized);
+        }
+        if !subscription.is_active {
+            return Err(PawChainError::SubscriptionInactive);
+        }
+
+        subscription.is_active = false;
+        Self::write_persistent(&env, &DataKey::Subscription(subscription_id), &subscription);
+
+        SubscriptionCancelled {
+            feeder_id: subscription.feeder_id,
+            donor,
+            subscription,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // Get subscription details
+    pub fn get_subscription(env: Env, subscription_id: u64) -> Result<Subscription, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Subscription(subscription_id))
+            .ok_or(PawChainError::SubscriptionNotFound)
+    }
+
+    // List subscriptions with ids in [start, start + limit)
+    pub fn list_subscriptions(env: Env, start: u64, limit: u32) -> Vec<Subscription> {
+        Self::list_records(
+            &env,
+            &DataKey::SubscriptionCount,
+            DataKey::Subscription,
+            start,
+            limit,
+        )
+    }
+
+    // ========== SPONSORSHIPS ==========
+
+    // Pledge a monthly amount to an active dog; one live sponsorship per donor and dog
+    pub fn sponsor_dog(
+        env: Env,
+        donor: Address,
+        dog_id: u64,
+        monthly_amount: i128,
+    ) -> Result<u64, PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if monthly_amount <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+        if !Self::load_dog(&env, dog_id)?.is_active {
+            return Err(PawChainError::DogInactive);
+        }
+        let active_key = DataKey::ActiveSponsorship(donor.clone(), dog_id);
+        if env.storage().persistent().has(&active_key) {
+            return Err(PawChainError::AlreadySponsoring);
+        }
+
+        let mut sponsorship_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SponsorshipCount)
+            .unwrap_or(0);
+        sponsorship_count += 1;
+
+        let sponsorship = Sponsorship {
+            sponsorship_id: sponsorship_count,
+            donor: donor.clone(),
+            dog_id,
+            monthly_amount,
+            started_at: env.ledger().timestamp(),
+            ended_at: None,
+            is_active: true,
+        };
+
+        Self::write_persistent(&env, &DataKey::Sponsorship(sponsorship_count), &sponsorship);
+        env.storage()
+            .instance()
+            .set(&DataKey::SponsorshipCount, &sponsorship_count);
+        Self::write_persistent(&env, &active_key, &sponsorship_count);
+        Self::index_append(&env, IndexKey::DogSponsorships(dog_id), sponsorship_count);
+        Self::index_append(
+            &env,
+            IndexKey::DonorSponsorships(donor.clone()),
+            sponsorship_count,
+        );
+
+        SponsorshipStarted {
+            dog_id,
+            donor,
+            sponsorship,
+        }
+        .publish(&env);
+
+        Ok(sponsorship_count)
+    }
+
+    // End a sponsorship (sponsoring donor only)
+    pub fn end_sponsorship(
+        env: Env,
+        donor: Address,
+        sponsorship_id: u64,
+    ) -> Result<(), PawChainError> {
+        donor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let sponsorship = Self::get_sponsorship(env.clone(), sponsorship_id)?;
+        if sponsorship.donor != donor {
+            return Err(PawChainError::Unauthorized);
+        }
+        if !sponsorship.is_active {
+            return Err(PawChainError::SponsorshipInactive);
+        }
+        Self::close_sponsorship(&env, sponsorship);
+        Ok(())
+    }
+
+    // Get sponsorship details
+    pub fn get_sponsorship(env: Env, sponsorship_id: u64) -> Result<Sponsorship, PawChainError> {
+        let sponsorship: Sponsorship =
+            Self::read_persistent(&env, &DataKey::Sponsorship(sponsorship_id))
+                .ok_or(PawChainError::SponsorshipNotFound)?;
+        let dog = Self::load_dog(&env, sponsorship.dog_id)?;
+        Ok(Self::sponsorship_view(sponsorship, &dog))
+    }
+
+    // List a dog's sponsorships, past and present, entries [start, start + limit)
+    pub fn get_dog_sponsors(
+        env: Env,
+        dog_id: u64,
+        start: u64,
+        limit: u32,
+    ) -> Result<Vec<Sponsorship>, PawChainError> {
+        let dog = Self::load_dog(&env, dog_id)?;
+        let ids = Self::index_range(&env, &IndexKey::DogSponsorships(dog_id), start, limit);
+        let mut sponsorships = Vec::new(&env);
+        for sponsorship in Self::load_records(&env, &ids, DataKey::Sponsorship) {
+            sponsorships.push_back(Self::sponsorship_view(sponsorship, &dog));
+        }
+        Ok(sponsorships)
+    }
+
+    // List a donor's sponsorships, past and present, entries [start, start + limit)
+    pub fn get_donor_sponsorships(
+        env: Env,
+        donor: Address,
+        start: u64,
+        limit: u32,
+    ) -> Vec<Sponsorship> {
+        let ids = Self::index_range(&env, &IndexKey::DonorSponsorships(donor), start, limit);
+        let mut sponsorships = Vec::new(&env);
+        for sponsorship in Self::load_records::<Sponsorship>(&env, &ids, DataKey::Sponsorship) {
+            let sponsorship = match Self::load_dog(&env, sponsorship.dog_id) {
+                Ok(dog) => Self::sponsorship_view(sponsorship, &dog),
+                Err(_) => sponsorship,
+            };
+            sponsorships.push_back(sponsorship);
+        }
+        sponsorships
+    }
+
+    // ========== EXPENSE TRACKING ==========
+
+    // Record an expense paid in an allowlisted token
+    pub fn record_expense(
+        env: Env,
+        feeder: Address,
+        token: Address,
+        amount: i128,
+        funding_source: FundingSource,
+        category: ExpenseCategory,
+        description: String,
+        receipt_hash: String,
+        dogs_affected: Vec<u64>,
+    ) -> Result<u64, PawChainError> {
+        feeder.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if amount <= 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+
+        Self::require_allowed_token(&env, &token)?;
+        let feeder_id = Self::get_feeder_id_by_address(&env, &feeder)?;
+        Self::require_distinct_dogs(&env, &dogs_affected, feeder_id)?;
+        let counter = Self::category_counter(&env, &category)?;
+
+        // PawChain-funded spend cannot exceed what the feeder received
+        let mut ledger = Self::load_feeder_ledger(&env, feeder_id);
+        if funding_source == FundingSource::PawChain
+            && Self::get_feeder_balance(env.clone(), feeder_id, token.clone())?.available < amount
+        {
+            return Err(PawChainError::InsufficientBalance);
+        }
+        Self::charge_ledger(&mut ledger, funding_source, &token, amount);
+        Self::write_persistent(&env, &DataKey::FeederLedger(feeder_id), &ledger);
+
+        let mut expense_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ExpenseCount)
+            .unwrap_or(0);
+        expense_count += 1;
+
+        // Link the expense to each dog's history
+        let mut fund_draws = Map::new(&env);
+        if !dogs_affected.is_empty() {
+            let share = amount / dogs_affected.len() as i128;
+            for dog_id in dogs_affected.iter() {
+                Self::index_append(&env, IndexKey::DogExpenses(dog_id), expense_count);
+                let mut spend = Self::load_dog_spend(&env, dog_id);
+                Self::add_amount(&mut spend.expense_share, &token, share);
+                Self::write_persistent(&env, &DataKey::DogSpend(dog_id), &spend);
+                if funding_source == FundingSource::PawChain {
+                    let drawn = Self::draw_dog_fund(&env, dog_id, &token, share);
+                    if drawn > 0 {
+                        fund_draws.set(dog_id, drawn);
+                    }
+                }
+            }
+        }
+
+        let expense = ExpenseRecord {
+            expense_id: expense_count,
+            feeder_id,
+            token: token.clone(),
+            amount,
+            funding_source,
+            category: category.clone(),
+            description,
+            timestamp: env.ledger().timestamp(),
+            receipt_hash,
+            dogs_affected: dogs_affected.clone(),
+            fund_draws,
+            status: ExpenseStatus::Pending,
+            rejection_reason: None,
+        };
+
+        Self::write_persistent(&env, &DataKey::Expense(expense_count), &expense);
+        env.storage()
+            .instance()
+            .set(&DataKey::ExpenseCount, &expense_count);
+        Self::index_append(&env, IndexKey::FeederExpenses(feeder_id), expense_count);
+        Self::index_append(&env, IndexKey::PendingExpenses, expense_count);
+
+        // Update feeder total spent
+        let mut feeder_data = Self::load_feeder(&env, feeder_id)?;
+        Self::add_amount(&mut feeder_data.total_spent, &token, amount);
+        Self::write_persistent(&env, &DataKey::Feeder(feeder_id), &feeder_data);
+
+        // Update stats based on category
+        if let Some(counter) = counter {
+            Self::increment_stat_by(&env, feeder_id, counter, dogs_affected.len() as u64)?;
+        }
+
+        ExpenseRecorded {
+            feeder_id,
+            expense_id: expense_count,
+            expense,
+        }
+        .publish(&env);
+
+        Ok(expense_count)
+    }
+
+    // Get expense details
+    pub fn get_expense(env: Env, expense_id: u64) -> Result<ExpenseRecord, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Expense(expense_id))
+            .ok_or(PawChainError::ExpenseNotFound)
+    }
+
+    // Verify an expense against its receipt (admin only). A PawChain-funded
+    // expense releases up to its amount from the feeder's escrow balance in the
+    // expense's token; self-funded and external spend leaves escrow untouched.
+    pub fn verify_expense(
+        env: Env,
+        verifier: Address,
+        expense_id: u64,
+    ) -> Result<i128, PawChainError> {
+        Self::require_admin(&env, &verifier)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut expense = Self::get_expense(env.clone(), expense_id)?;
+        match expense.status {
+            ExpenseStatus::Pending => {}
+            ExpenseStatus::Verified => return Err(PawChainError::ExpenseAlreadyVerified),
+            ExpenseStatus::Rejected => return Err(PawChainError::ExpenseNotPending),
+        }
+        expense.status = ExpenseStatus::Verified;
+        Self::write_persistent(&env, &DataKey::Expense(expense_id), &expense);
+        Self::advance_pending_head(&env);
+
+        let feeder_id = expense.feeder_id;
+        let mut escrow = Self::load_escrow(&env, feeder_id);
+        let balance = escrow.get(expense.token.clone()).unwrap_or(0);
+        let released = match expense.funding_source {
+            FundingSource::PawChain => balance.min(expense.amount),
+            FundingSource::SelfFunded | FundingSource::External => 0,
+        };
+        if released > 0 {
+            let feeder = Self::load_feeder(&env, feeder_id)?;
+            token::Client::new(&env, &expense.token).transfer(
+                &env.current_contract_address(),
+                &feeder.wallet_address,
+                &released,
+            );
+            Self::add_amount(&mut escrow, &expense.token, -released);
+            Self::write_persistent(&env, &DataKey::EscrowBalance(feeder_id), &escrow);
+
+            EscrowReleased {
+                feeder_id,
+                expense_id,
+                amount: released,
+            }
+            .publish(&env);
+        }
+
+        ExpenseVerified {
+            feeder_id,
+            expense_id,
+            expense,
+        }
+        .publish(&env);
+
+        Ok(released)
+    }
+
+    // Reject a pending expense (admin only), reversing it out of the feeder's
+    // total spent, the dogs' spend and the activity stats
+    pub fn reject_expense(
+        env: Env,
+        verifier: Address,
+        expense_id: u64,
+        reason: String,
+    ) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &verifier)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut expense = Self::get_expense(env.clone(), expense_id)?;
+        if expense.status != ExpenseStatus::Pending {
+            return Err(PawChainError::ExpenseNotPending);
+        }
+        expense.status = ExpenseStatus::Rejected;
+        expense.rejection_reason = Some(reason);
+        Self::write_persistent(&env, &DataKey::Expense(expense_id), &expense);
+        Self::advance_pending_head(&env);
+
+        let feeder_id = expense.feeder_id;
+        let mut feeder = Self::load_feeder(&env, feeder_id)?;
+        Self::add_amount(&mut feeder.total_spent, &expense.token, -expense.amount);
+        Self::write_persistent(&env, &DataKey::Feeder(feeder_id), &feeder);
+        let mut ledger = Self::load_feeder_ledger(&env, feeder_id);
+        Self::charge_ledger(
+            &mut ledger,
+            expense.funding_source,
+            &expense.token,
+            -expense.amount,
+        );
+        Self::write_persistent(&env, &DataKey::FeederLedger(feeder_id), &ledger);
+
+        if !expense.dogs_affected.is_empty() {
+            let share = expense.amount / expense.dogs_affected.len() as i128;
+            for dog_id in expense.dogs_affected.iter() {
+                let mut spend = Self::load_dog_spend(&env, dog_id);
+                Self::add_amount(&mut spend.expense_share, &expense.token, -share);
+                Self::write_persistent(&env, &DataKey::DogSpend(dog_id), &spend);
+            }
+        }
+        for (dog_id, drawn) in expense.fund_draws.iter() {
+            Self::earmark_for_dog(&env, dog_id, &expense.token, drawn);
+        }
+
+        // Legacy custom names that were never registered counted nothing
+        if let Some(counter) = Self::category_counter(&env, &expense.category).unwrap_or(None) {
+            Self::decrement_stat_by(&env, feeder_id, counter, expense.dogs_affected.len() as u64)?;
+        }
+
+        ExpenseRejected {
+            feeder_id,
+            expense_id,
+            expense,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // List expenses awaiting review, oldest first, from queue entries [start, start + limit)
+    // past the head. Entries decided since are skipped, so a page can come back short.
+    pub fn list_pending_expenses(env: Env, start: u64, limit: u32) -> Vec<ExpenseRecord> {
+        let ids = Self::index_range(
+            &env,
+            &IndexKey::PendingExpenses,
+            Self::pending_head(&env).saturating_add(start),
+            limit,
+        );
+        let mut pending = Vec::new(&env);
+        for expense in Self::load_records::<ExpenseRecord>(&env, &ids, DataKey::Expense) {
+            if expense.status == ExpenseStatus::Pending {
+                pending.push_back(expense);
+            }
+        }
+        pending
+    }
+
+    // List expenses with ids in [start, start + limit)
+    pub fn list_expenses(env: Env, start: u64, limit: u32) -> Vec<ExpenseRecord> {
+        Self::list_records(&env, &DataKey::ExpenseCount, DataKey::Expense, start, limit)
+    }
+
+    // ========== VETERINARIANS ==========
+
+    // Register a veterinarian; one profile per wallet
+    pub fn register_vet(
+        env: Env,
+        wallet_address: Address,
+        name: String,
+        license_number: String,
+        clinic: String,
+    ) -> Result<u64, PawChainError> {
+        wallet_address.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let address_key = DataKey::Vet(VetKey::ByAddress(wallet_address.clone()));
+        if env.storage().persistent().has(&address_key) {
+            return Err(PawChainError::VetAlreadyRegistered);
+        }
+
+        let mut vet_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VetCount)
+            .unwrap_or(0);
+        vet_count += 1;
+
+        let vet = VetProfile {
+            vet_id: vet_count,
+            name,
+            license_number,
+            clinic,
+            wallet_address: wallet_address.clone(),
+            registered_date: env.ledger().timestamp(),
+            is_verified: false,
+        };
+
+        Self::write_persistent(&env, &DataKey::Vet(VetKey::Profile(vet_count)), &vet);
+        env.storage().instance().set(&DataKey::VetCount, &vet_count);
+        Self::write_persistent(&env, &address_key, &vet_count);
+
+        VetRegistered {
+            vet_id: vet_count,
+            wallet_address,
+            vet,
+        }
+        .publish(&env);
+
+        Ok(vet_count)
+    }
+
+    // Verify a vet's license (admin only). Only verified vets can attest treatments.
+    pub fn verify_vet(env: Env, admin: Address, vet_id: u64) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut vet = Self::load_vet(&env, vet_id)?;
+        vet.is_verified = true;
+        Self::write_persistent(&env, &DataKey::Vet(VetKey::Profile(vet_id)), &vet);
+
+        VetVerified { vet_id, admin, vet }.publish(&env);
+        Ok(())
+    }
+
+    // Get vet profile
+    pub fn get_vet(env: Env, vet_id: u64) -> Result<VetProfile, PawChainError> {
+        Self::load_vet(&env, vet_id)
+    }
+
+    // List vets with ids in [start, start + limit)
+    pub fn list_vets(env: Env, start: u64, limit: u32) -> Vec<VetProfile> {
+        Self::list_records(
+            &env,
+            &DataKey::VetCount,
+            |id| DataKey::Vet(VetKey::Profile(id)),
+            start,
+            limit,
+        )
+    }
+
+    // Countersign a treatment that names the calling vet, marking it vet-attested
+    pub fn countersign_treatment(
+        env: Env,
+        vet: Address,
+        treatment_id: u64,
+    ) -> Result<(), PawChainError> {
+        vet.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let vet_id: u64 = Self::read_persistent(&env, &DataKey::Vet(VetKey::ByAddress(vet)))
+            .ok_or(PawChainError::VetNotFound)?;
+        let mut treatment = Self::get_treatment(env.clone(), treatment_id)?;
+        if treatment.vet_id != Some(vet_id) {
+            return Err(PawChainError::Unauthorized);
+        }
+        if treatment.vet_attested {
+            return Err(PawChainError::AlreadyAttested);
+        }
+        if !Self::load_vet(&env, vet_id)?.is_verified {
+            return Err(PawChainError::VetNotVerified);
+        }
+
+        treatment.vet_attested = true;
+        Self::write_persistent(&env, &DataKey::Treatment(treatment_id), &treatment);
+
+        TreatmentVetAttested {
+            vet_id,
+            treatment_id,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // ========== TREATMENT RECORDS ==========
+
+    // Record a medical treatment, priced in the default token. Its cost is charged to
+    // the feeder's ledger like an expense; PawChain-funded treatments also draw on
+    // the dog's earmarked funds.
+    pub fn record_treatment(
+        env: Env,
+        feeder: Address,
+        dog_id: u64,
+        treatment_type: String,
+        description: String,
+        cost: i128,
+        funding_source: FundingSource,
+        veterinarian: String,
+        vet_id: Option<u64>,
+        vet_cosign: bool,
+        outcome: String,
+    ) -> Result<u64, PawChainError> {
+        feeder.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        if cost < 0 {
+            return Err(PawChainError::InvalidAmount);
+        }
+
+        let feeder_id = Self::get_feeder_id_by_address(&env, &feeder)?;
+        Self::load_feeder_dog(&env, dog_id, feeder_id)?;
+
+        // A referenced vet's registered name replaces the free-text one; a co-signing
+        // vet must be verified and sign this call
+        let vet = match vet_id {
+            Some(vet_id) => Some(Self::load_vet(&env, vet_id)?),
+            None => None,
+        };
+        let veterinarian = match &vet {
+            Some(vet) => vet.name.clone(),
+            None => veterinarian,
+        };
+        if vet_cosign {
+            let vet = vet.as_ref().ok_or(PawChainError::VetNotFound)?;
+            if !vet.is_verified {
+                return Err(PawChainError::VetNotVerified);
+            }
+            vet.wallet_address.require_auth();
+        }
+
+        // PawChain-funded spend cannot exceed what the feeder received
+        let token = Self::token_client(&env)?.address;
+        let mut ledger = Self::load_feeder_ledger(&env, feeder_id);
+        if funding_source == FundingSource::PawChain
+            && Self::get_feeder_balance(env.clone(), feeder_id, token.clone())?.available < cost
+        {
+            return Err(PawChainError::InsufficientBalance);
+        }
+        Self::charge_ledger(&mut ledger, funding_source, &token, cost);
+        Self::write_persistent(&env, &DataKey::FeederLedger(feeder_id), &ledger);
+
+        let mut treatment_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TreatmentCount)
+            .unwrap_or(0);
+        treatment_count += 1;
+
+        let treatment = TreatmentRecord {
+            treatment_id: treatment_count,
+            dog_id,
+            feeder_id,
+            treatment_type,
+            description,
+            cost,
+            funding_source,
+            date: env.ledger().timestamp(),
+            veterinarian,
+            vet_id,
+            vet_attested: vet_cosign,
+            outcome,
+        };
+
+        Self::write_persistent(&env, &DataKey::Treatment(treatment_count), &treatment);
+        env.storage()
+            .instance()
+            .set(&DataKey::TreatmentCount, &treatment_count);
+        Self::index_append(&env, IndexKey::DogTreatments(dog_id), treatment_count);
+
+        let mut spend = Self::load_dog_spend(&env, dog_id);
+        spend.treatment_cost += cost;
+        Self::write_persistent(&env, &DataKey::DogSpend(dog_id), &spend);
+        if funding_source == FundingSource::PawChain {
+            Self::draw_dog_fund(&env, dog_id, &token, cost);
+        }
+
+        Self::increment_stat(&env, feeder_id, ActivityCounter::Treated)?;
+
+        if let (Some(vet_id), true) = (vet_id, vet_cosign) {
+            TreatmentVetAttested {
+                vet_id,
+                treatment_id: treatment_count,
+            }
+            .publish(&env);
+        }
+        TreatmentRecorded {
+            feeder_id,
+            dog_id,
+            treatment,
+        }
+        .publish(&env);
+
+        Ok(treatment_count)
+    }
+
+    // Get treatment details
+    pub fn get_treatment(env: Env, treatment_id: u64) -> Result<TreatmentRecord, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Treatment(treatment_id))
+            .ok_or(PawChainError::TreatmentNotFound)
+    }
+
+    // List treatments with ids in [start, start + limit)
+    pub fn list_treatments(env: Env, start: u64, limit: u32) -> Vec<TreatmentRecord> {
+        Self::list_records(
+            &env,
+            &DataKey::TreatmentCount,
+            DataKey::Treatment,
+            start,
+            limit,
+        )
+    }
+
+    // ========== AUDITS ==========
+
+    // Register an independent auditor (admin only); re-adding a removed auditor
+    // reactivates them
+    pub fn add_auditor(
+        env: Env,
+        admin: Address,
+        auditor: Address,
+        name: String,
+    ) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let key = DataKey::Audit(AuditKey::Auditor(auditor.clone()));
+        let profile = match Self::read_persistent::<Auditor>(&env, &key) {
+            Some(existing) if existing.is_active => {
+                return Err(PawChainError::AuditorAlreadyRegistered)
+            }
+            Some(existing) => Auditor {
+                name,
+                is_active: true,
+                ..existing
+            },
+            None => {
+                Self::index_append(&env, IndexKey::Auditors, auditor.clone());
+                Auditor {
+                    address: auditor.clone(),
+                    name,
+                    added_at: env.ledger().timestamp(),
+                    attestation_count: 0,
+                    is_active: true,
+                }
+            }
+        };
+        Self::write_persistent(&env, &key, &profile);
+
+        AuditorRegistryUpdated {
+            auditor,
+            active: true,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // Stop an auditor from attesting further records (admin only). Their past
+    // attestations stand.
+    pub fn remove_auditor(env: Env, admin: Address, auditor: Address) -> Result<(), PawChainError> {
+        Self::require_admin(&env, &admin)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut profile = Self::load_active_auditor(&env, &auditor)?;
+        profile.is_active = false;
+        Self::write_persistent(
+            &env,
+            &DataKey::Audit(AuditKey::Auditor(auditor.clone())),
+            &profile,
+        );
+
+        AuditorRegistryUpdated {
+            auditor,
+            active: false,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // Get an auditor's profile
+    pub fn get_auditor(env: Env, auditor: Address) -> Result<Auditor, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Audit(AuditKey::Auditor(auditor)))
+            .ok_or(PawChainError::AuditorNotFound)
+    }
+
+    // List everyone ever registered as an auditor, entries [start, start + limit)
+    pub fn list_auditors(env: Env, start: u64, limit: u32) -> Vec<Auditor> {
+        let addresses: Vec<Address> = Self::index_range(&env, &IndexKey::Auditors, start, limit);
+        let mut auditors = Vec::new(&env);
+        for address in addresses.iter() {
+            if let Some(auditor) =
+                Self::read_persistent(&env, &DataKey::Audit(AuditKey::Auditor(address)))
+            {
+                auditors.push_back(auditor);
+            }
+        }
+        auditors
+    }
+
+    // Attest an expense (active auditors only, once per expense)
+    pub fn attest_expense(
+        env: Env,
+        auditor: Address,
+        expense_id: u64,
+        note_hash: String,
+    ) -> Result<u64, PawChainError> {
+        auditor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let expense = Self::get_expense(env.clone(), expense_id)?;
+        Self::record_attestation(
+            &env,
+            auditor,
+            AttestationSubject::Expense(expense_id),
+            expense.feeder_id,
+            note_hash,
+        )
+    }
+
+    // Attest a treatment (active auditors only, once per treatment)
+    pub fn attest_treatment(
+        env: Env,
+        auditor: Address,
+        treatment_id: u64,
+        note_hash: String,
+    ) -> Result<u64, PawChainError> {
+        auditor.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let treatment = Self::get_treatment(env.clone(), treatment_id)?;
+        Self::record_attestation(
+            &env,
+            auditor,
+            AttestationSubject::Treatment(treatment_id),
+            treatment.feeder_id,
+            note_hash,
+        )
+    }
+
+    // Get attestation details
+    pub fn get_attestation(env: Env, attestation_id: u64) -> Result<Attestation, PawChainError> {
+        Self::read_persistent(&env, &DataKey::Audit(AuditKey::Attestation(attestation_id)))
+            .ok_or(PawChainError::AttestationNotFound)
+    }
+
+    // List the attestations of an expense or treatment, entries [start, start + limit)
+    pub fn get_attestations(
+        env: Env,
+        subject: AttestationSubject,
+        start: u64,
+        limit: u32,
+    ) -> Vec<Attestation> {
+        let ids = Self::index_range(&env, &IndexKey::SubjectAttestations(subject), start, limit);
+        Self::load_records(&env, &ids, |id| DataKey::Audit(AuditKey::Attestation(id)))
+    }
+
+    // Count the attestations of an expense or treatment
+    pub fn get_attestation_count(env: Env, subject: AttestationSubject) -> u64 {
+        Self::index_len(&env, &IndexKey::SubjectAttestations(subject))
+    }
+
+    // Get how many attestations a feeder's records have, and from how many auditors
+    pub fn get_feeder_audit_summary(
+        env: Env,
+        feeder_id: u64,
+    ) -> Result<FeederAuditSummary, PawChainError> {
+        Self::load_feeder(&env, feeder_id)?;
+        Ok(Self::load_feeder_audit(&env, feeder_id))
+    }
+
+    // ========== HELPER FUNCTIONS ==========
+
+    // Upgrade records written under `from_version` to `from_version + 1`, starting at
+    // record id `cursor`. Returns the next cursor while records remain, `None` when done.
+    fn migrate_step(
+        env: &Env,
+        from_version: u32,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<Option<u64>, PawChainError> {
+        let next = match from_version {
+            // v1: records move from instance to persistent storage, gaining the address
+            // index, per-feeder/dog/donor indexes, dog spend and donor summaries
+            0 => {
+                let token = Self::token_client(env)?.address;
+                let mut count: u64 = 0;
+                for count_key in [
+                    DataKey::DogCount,
+                    DataKey::FeederCount,
+                    DataKey::DonationCount,
+                    DataKey::ExpenseCount,
+                    DataKey::TreatmentCount,
+                ] {
+                    count = count.max(env.storage().instance().get(&count_key).unwrap_or(0));
+                }
+                let end = count.min(cursor + limit as u64 - 1);
+                for id in cursor..=end {
+                    Self::migrate_instance_records(env, id, &token);
+                }
+                if end >= count {
+                    None
+                } else {
+                    Some(end + 1)
+                }
+            }
+            // v2: Donation gains `escrowed`
+            1 => Self::migrate_records(
+                env,
+                &DataKey::DonationCount,
+                DataKey::Donation,
+                cursor,
+                limit,
+                |env, record| {
+                    record.set(Symbol::new(env, "escrowed"), false.into_val(env));
+                },
+            ),
+            // v3: Donation gains `token`; the donor's DonorSummary.total_given becomes per token.
+            // Contracts from before the allowlist start out accepting the default token.
+            2 => {
+                let token = Self::token_client(env)?.address;
+                let allowlist_key = DataKey::Config(ConfigKey::AllowedTokens);
+                if !env.storage().instance().has(&allowlist_key) {
+                    env.storage()
+                        .instance()
+                        .set(&allowlist_key, &Vec::from_array(env, [token.clone()]));
+                }
+                Self::migrate_records(
+                    env,
+                    &DataKey::DonationCount,
+                    DataKey::Donation,
+                    cursor,
+                    limit,
+                    |env, record| {
+                        record.set(Symbol::new(env, "token"), token.into_val(env));
+                        let donor = record.get(Symbol::new(env, "donor_address")).unwrap();
+                        let summary_key =
+                            DataKey::DonorSummary(Address::try_from_val(env, &donor).unwrap());
+                        Self::migrate_entry(env, &summary_key, |env, summary| {
+                            Self::migrate_per_token(env, summary, "total_given", &token);
+                        });
+                    },
+                )
+            }
+            // v4: ExpenseRecord gains `token`
+            3 => {
+                let token = Self::token_client(env)?.address;
+                Self::migrate_records(
+                    env,
+                    &DataKey::ExpenseCount,
+                    DataKey::Expense,
+                    cursor,
+                    limit,
+                    |env, record| {
+                        record.set(Symbol::new(env, "token"), token.into_val(env));
+                    },
+                )
+            }
+            // v5: FeederProfile totals and the feeder's escrow balance become per token
+            4 => {
+                let token = Self::token_client(env)?.address;
+                Self::migrate_records(
+                    env,
+                    &DataKey::FeederCount,
+                    DataKey::Feeder,
+                    cursor,
+                    limit,
+                    |env, record| {
+                        Self::migrate_per_token(env, record, "total_received", &token);
+                        Self::migrate_per_token(env, record, "total_spent", &token);
+                        let feeder_id = record.get(Symbol::new(env, "feeder_id")).unwrap();
+                        let escrow_key =
+                            DataKey::EscrowBalance(u64::try_from_val(env, &feeder_id).unwrap());
+                        let balance: Option<i128> = env.storage().persistent().get(&escrow_key);
+                        if let Some(balance) = balance {
+                            let escrow = Map::from_array(env, [(token.clone(), balance)]);
+                            Self::write_persistent(env, &escrow_key, &escrow);
+                        }
+                    },
+                )
+            }
+            // v6: DogSpend.expense_share becomes per token
+            5 => {
+                let token = Self::token_client(env)?.address;
+                Self::migrate_records(
+                    env,
+                    &DataKey::DogCount,
+                    DataKey::DogSpend,
+                    cursor,
+                    limit,
+                    |env, record| {
+                        Self::migrate_per_token(env, record, "expense_share", &token);
+                    },
+                )
+            }
+            // v7: Donation gains `batch_id`
+            6 => Self::migrate_records(
+                env,
+                &DataKey::DonationCount,
+                DataKey::Donation,
+                cursor,
+                limit,
+                |env, record| {
+                    record.set(Symbol::new(env, "batch_id"), None::<u64>.into_val(env));
+                },
+            ),
+            // v8: Donation gains `match_of`
+            7 => Self::migrate_records(
+                env,
+                &DataKey::DonationCount,
+                DataKey::Donation,
+                cursor,
+                limit,
+                |env, record| {
+                    record.set(Symbol::new(env, "match_of"), None::<u64>.into_val(env));
+                },
+            ),
+            // v9: Donation gains `fee`
+            8 => Self::migrate_records(
+                env,
+                &DataKey::DonationCount,
+                DataKey::Donation,
+                cursor,
+                limit,
+                |env, record| {
+                    record.set(Symbol::new(env, "fee"), 0i128.into_val(env));
+                },
+            ),
+            // v10: ExpenseRecord's `verified` flag becomes `status`, plus `rejection_reason`;
+            // unverified expenses join the review queue
+            9 => Self::migrate_records(
+                env,
+                &DataKey::ExpenseCount,
+                DataKey::Expense,
+                cursor,
+                limit,
+                |env, record| {
+                    let verified_key = Symbol::new(env, "verified");
+                    let verified = match record.get(verified_key.clone()) {
+                        Some(verified) => bool::try_from_val(env, &verified).unwrap_or(false),
+                        None => return,
+                    };
+                    record.remove(verified_key);
+                    let status = if verified {
+                        ExpenseStatus::Verified
+                    } else {
+                        let expense_id = record.get(Symbol::new(env, "expense_id")).unwrap();
+                        Self::index_append(
+                            env,
+                            IndexKey::PendingExpenses,
+                            u64::try_from_val(env, &expense_id).unwrap(),
+                        );
+                        ExpenseStatus::Pending
+                    };
+                    record.set(Symbol::new(env, "status"), status.into_val(env));
+                    record.set(
+                        Symbol::new(env, "rejection_reason"),
+                        None::<String>.into_val(env),
+                    );
+                },
+            ),
+            // v11: ExpenseRecord gains `funding_source`. The source of older expenses is
+            // unknown, so they are booked as External and kept out of PawChain spend.
+            10 => Self::migrate_records(
+                env,
+                &DataKey::ExpenseCount,
+                DataKey::Expense,
+                cursor,
+                limit,
+                |env, record| {
+                    let key = Symbol::new(env, "funding_source");
+                    if record.contains_key(key.clone()) {
+                        return;
+                    }
+                    record.set(key, FundingSource::External.into_val(env));
+                    let status: ExpenseStatus = record
+                        .get(Symbol::new(env, "status"))
+                        .and_then(|status| ExpenseStatus::try_from_val(env, &status).ok())
+                        .unwrap_or(ExpenseStatus::Pending);
+                    if status == ExpenseStatus::Rejected {
+                        return;
+                    }
+                    let field = |name: &str| record.get(Symbol::new(env, name)).unwrap();
+                    let feeder_id = u64::try_from_val(env, &field("feeder_id")).unwrap();
+                    let token = Address::try_from_val(env, &field("token")).unwrap();
+                    let amount = i128::try_from_val(env, &field("amount")).unwrap();
+                    let mut ledger = Self::load_feeder_ledger(env, feeder_id);
+                    Self::charge_ledger(&mut ledger, FundingSource::External, &token, amount);
+                    Self::write_persistent(env, &DataKey::FeederLedger(feeder_id), &ledger);
+                },
+            ),
+            // v12: ExpenseRecord.category becomes an ExpenseCategory; unrecognised
+            // names are kept as Custom
+            11 => Self::migrate_records(
+                env,
+                &DataKey::ExpenseCount,
+                DataKey::Expense,
+                cursor,
+                limit,
+                |env, record| {
+                    let key = Symbol::new(env, "category");
+                    let name = match record
+                        .get(key.clone())
+                        .and_then(|value| String::try_from_val(env, &value).ok())
+                    {
+                        Some(name) => name,
+                        None => return,
+                    };
+                    let category = if name == String::from_str(env, "Food") {
+                        ExpenseCategory::Food
+                    } else if name == String::from_str(env, "Vaccination") {
+                        ExpenseCategory::Vaccination
+                    } else if name == String::from_str(env, "Spaying") {
+                        ExpenseCategory::Spaying
+                    } else if name == String::from_str(env, "Neutering") {
+                        ExpenseCategory::Neutering
+                    } else if name == String::from_str(env, "Treatment") {
+                        ExpenseCategory::Treatment
+                    } else if name == String::from_str(env, "Medicine") {
+                        ExpenseCategory::Medicine
+                    } else {
+                        ExpenseCategory::Custom(name)
+                    };
+                    record.set(key, category.into_val(env));
+                },
+            ),
+            // v13: TreatmentRecord gains `vet_id` and `vet_attested`; older treatments
+            // name their vet only as free text
+            12 => Self::migrate_records(
+                env,
+                &DataKey::TreatmentCount,
+                DataKey::Treatment,
+                cursor,
+                limit,
+                |env, record| {
+                    let key = Symbol::new(env, "vet_attested");
+                    if !record.contains_key(key.clone()) {
+                        record.set(Symbol::new(env, "vet_id"), None::<u64>.into_val(env));
+                        record.set(key, false.into_val(env));
+                    }
+                },
+            ),
+            // v14: ExpenseRecord gains `fund_draws`. Earmark draws of older expenses
+            // were not recorded, so rejecting one of them restores nothing.
+            13 => Self::migrate_records(
+                env,
+                &DataKey::ExpenseCount,
+                DataKey::Expense,
+                cursor,
+                limit,
+                |env, record| {
+                    let key = Symbol::new(env, "fund_draws");
+                    if !record.contains_key(key.clone()) {
+                        record.set(key, Map::<u64, i128>::new(env).into_val(env));
+                    }
+                },
+            ),
+            // v15: the review queue moves from one Vec of ids into a bucketed index
+            14 => {
+                let legacy: Option<Vec<u64>> =
+                    env.storage().persistent().get(&LegacyKey::PendingExpenses);
+                match legacy {
+                    Some(ids) => {
+                        let end = (ids.len() as u64).min(cursor + limit as u64 - 1);
+                        for position in cursor..=end {
+                            Self::index_append(
+                                env,
+                                IndexKey::PendingExpenses,
+                                ids.get_unchecked(position as u32 - 1),
+                            );
+                        }
+                        if end >= ids.len() as u64 {
+                            env.storage()
+                                .persistent()
+                                .remove(&LegacyKey::PendingExpenses);
+                            None
+                        } else {
+                            Some(end + 1)
+                        }
+                    }
+                    None => None,
+                }
+            }
+            // v16: the auditor list moves from instance storage into a bucketed index
+            15 => {
+                let legacy: Option<Vec<Address>> = env.storage().instance().get(&DataKey::Auditors);
+                match legacy {
+                    Some(auditors) => {
+                        let end = (auditors.len() as u64).min(cursor + limit as u64 - 1);
+                        for position in cursor..=end {
+                            Self::index_append(
+                                env,
+                                IndexKey::Auditors,
+                                auditors.get_unchecked(position as u32 - 1),
+                            );
+                        }
+                        if end >= auditors.len() as u64 {
+                            env.storage().instance().remove(&DataKey::Auditors);
+                            None
+                        } else {
+                            Some(end + 1)
+                        }
+                    }
+                    None => None,
+                }
+            }
+            // v17: Campaign gains `unrecorded` and `unrecorded_fee`. Campaigns paid
+            // out before then recorded their contributors when finalized.
+            16 => Self::migrate_records(
+                env,
+                &DataKey::CampaignCount,
+                DataKey::Campaign,
+                cursor,
+                limit,
+                |env, record| {
+                    let key = Symbol::new(env, "unrecorded");
+                    if !record.contains_key(key.clone()) {
+                        record.set(key, 0i128.into_val(env));
+                        record.set(Symbol::new(env, "unrecorded_fee"), 0i128.into_val(env));
+                    }
+                },
+            ),
+            // v18: TreatmentRecord gains `funding_source`. Older treatments were never
+            // charged to the feeder's ledger, so they count as self-funded.
+            17 => Self::migrate_records(
+                env,
+                &DataKey::TreatmentCount,
+                DataKey::Treatment,
+                cursor,
+                limit,
+                |env, record| {
+                    let key = Symbol::new(env, "funding_source");
+                    if !record.contains_key(key.clone()) {
+                        record.set(key, FundingSource::SelfFunded.into_val(env));
+                    }
+                },
+            ),
+            // v19: open appeals move from one Vec of ids into a bucketed index
+            18 => {
+                let legacy: Option<Vec<u64>> =
+                    env.storage().persistent().get(&LegacyKey::OpenEmergencies);
+                match legacy {
+                    Some(ids) => {
+                        let end = (ids.len() as u64).min(cursor + limit as u64 - 1);
+                        for position in cursor..=end {
+                            Self::index_append(
+                                env,
+                                IndexKey::Emergencies,
+                                ids.get_unchecked(position as u32 - 1),
+                            );
+                        }
+                        if end >= ids.len() as u64 {
+                            env.storage()
+                                .persistent()
+                                .remove(&LegacyKey::OpenEmergencies);
+                            None
+                        } else {
+                            Some(end + 1)
+                        }
+                    }
+                    None => None,
+                }
+            }
+            // v20: DogProfile gains `departed_at`. Sponsorships of dogs that left
+            // before then were ended when they left.
+            19 => Self::migrate_records(
+                env,
+                &DataKey::DogCount,
+                DataKey::Dog,
+                cursor,
+                limit,
+                |env, record| {
+                    let key = Symbol::new(env, "departed_at");
+                    if !record.contains_key(key.clone()) {
+                        record.set(key, None::<u64>.into_val(env));
+                    }
+                },
+            ),
+            // Storage written by a newer contract cannot be migrated down
+            _ => return Err(PawChainError::SchemaNotMigratable),
+        };
+        Ok(next)
+    }
+
+    // Move the baseline records with id `id` out of instance storage and add them to
+    // the indexes and running totals that were introduced alongside persistent storage
+    fn migrate_instance_records(env: &Env, id: u64, token: &Address) {
+        if let Some(feeder) = Self::migrate_to_persistent(env, &DataKey::Feeder(id)) {
+            let wallet: Address = Self::raw_field(env, &feeder, "wallet_address");
+            Self::write_persistent(env, &DataKey::FeederByAddress(wallet), &id);
+        }
+        Self::migrate_to_persistent(env, &DataKey::FeederStats(id));
+
+        if let Some(dog) = Self::migrate_to_persistent(env, &DataKey::Dog(id)) {
+            let feeder_id: u64 = Self::raw_field(env, &dog, "feeder_id");
+            Self::index_append(env, IndexKey::FeederDogs(feeder_id), id);
+        }
+
+        if let Some(donation) = Self::migrate_to_persistent(env, &DataKey::Donation(id)) {
+            let feeder_id: u64 = Self::raw_field(env, &donation, "feeder_id");
+            let donor: Address = Self::raw_field(env, &donation, "donor_address");
+            Self::index_append(env, IndexKey::FeederDonations(feeder_id), id);
+            Self::index_append(env, IndexKey::DonorDonations(donor.clone()), id);
+            Self::add_to_donor_summary(
+                env,
+                donor,
+                feeder_id,
+                Self::raw_field(env, &donation, "dog_id"),
+                token,
+                Self::raw_field(env, &donation, "amount"),
+                Self::raw_field(env, &donation, "timestamp"),
+            );
+        }
+
+        if let Some(expense) = Self::migrate_to_persistent(env, &DataKey::Expense(id)) {
+            let feeder_id: u64 = Self::raw_field(env, &expense, "feeder_id");
+            let amount: i128 = Self::raw_field(env, &expense, "amount");
+            let dogs_affected: Vec<u64> = Self::raw_field(env, &expense, "dogs_affected");
+            Self::index_append(env, IndexKey::FeederExpenses(feeder_id), id);
+            if !dogs_affected.is_empty() {
+                let share = amount / dogs_affected.len() as i128;
+                for dog_id in dogs_affected.iter() {
+                    Self::index_append(env, IndexKey::DogExpenses(dog_id), id);
+                    let mut spend = Self::load_dog_spend(env, dog_id);
+                    Self::add_amount(&mut spend.expense_share, token, share);
+                    Self::write_persistent(env, &DataKey::DogSpend(dog_id), &spend);
+                }
+            }
+        }
+
+        if let Some(treatment) = Self::migrate_to_persistent(env, &DataKey::Treatment(id)) {
+            let dog_id: u64 = Self::raw_field(env, &treatment, "dog_id");
+            Self::index_append(env, IndexKey::DogTreatments(dog_id), id);
+            let mut spend = Self::load_dog_spend(env, dog_id);
+            spend.treatment_cost += Self::raw_field::<i128>(env, &treatment, "cost");
+            Self::write_persistent(env, &DataKey::DogSpend(dog_id), &spend);
+        }
+    }
+
+    // Move an instance entry to persistent storage as a raw field map, if it is still
+    // in instance storage
+    fn migrate_to_persistent(env: &Env, key: &DataKey) -> Option<Map<Symbol, Val>> {
+        let record: Map<Symbol, Val> = env.storage().instance().get(key)?;
+        env.storage().instance().remove(key);
+        Self::write_persistent(env, key, &record);
+        Some(record)
+    }
+
+    fn raw_field<V: TryFromVal<Env, Val>>(env: &Env, record: &Map<Symbol, Val>, name: &str) -> V {
+        V::try_from_val(env, &record.get(Symbol::new(env, name)).unwrap())
+            .unwrap_or_else(|_| panic!("malformed record field"))
+    }
+
+    // Rewrite records with ids in [cursor, cursor + limit) through `upgrade`, working on
+    // the raw field map so old layouts need no Rust type. Returns the next cursor, or
+    // `None` once the last record has been rewritten.
+    fn migrate_records(
+        env: &Env,
+        count_key: &DataKey,
+        record_key: fn(u64) -> DataKey,
+        cursor: u64,
+        limit: u32,
+        upgrade: impl Fn(&Env, &mut Map<Symbol, Val>),
+    ) -> Option<u64> {
+        let count: u64 = env.storage().instance().get(count_key).unwrap_or(0);
+        let end = count.min(cursor + limit as u64 - 1);
+        for id in cursor..=end {
+            Self::migrate_entry(env, &record_key(id), &upgrade);
+        }
+        if end >= count {
+            None
+        } else {
+            Some(end + 1)
+        }
+    }
+
+    // Rewrite a single record through `upgrade`, if it exists
+    fn migrate_entry(env: &Env, key: &DataKey, upgrade: impl Fn(&Env, &mut Map<Symbol, Val>)) {
+        let record: Option<Map<Symbol, Val>> = env.storage().persistent().get(key);
+        if let Some(mut record) = record {
+            upgrade(env, &mut record);
+            Self::write_persistent(env, key, &record);
+        }
+    }
 
-        feeder_count
+    // Turn a legacy single-token amount field into a map keyed by `token`. Fields that
+    // are already maps are left alone, so records touched twice stay correct.
+    fn migrate_per_token(env: &Env, record: &mut Map<Symbol, Val>, field: &str, token: &Address) {
+        let field = Symbol::new(env, field);
+        if let Some(value) = record.get(field.clone()) {
+            if let Ok(amount) = i128::try_from_val(env, &value) {
+                let totals = Map::from_array(env, [(token.clone(), amount)]);
+                record.set(field, totals.into_val(env));
+            }
+        }
     }
 
-    // Verify a feeder (admin only)
-    pub fn verify_feeder(env: Env, admin: Address, feeder_id: u64) {
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("Unauthorized");
+    fn require_allowed_token(env: &Env, token: &Address) -> Result<(), PawChainError> {
+        if Self::get_allowed_tokens(env.clone()).contains(token) {
+            Ok(())
+        } else {
+            Err(PawChainError::TokenNotAllowed)
         }
+    }
 
-        let mut feeder: FeederProfile = env
+    // Client for the default token
+    fn token_client(env: &Env) -> Result<token::Client<'_>, PawChainError> {
+        let token_address: Address = env
             .storage()
             .instance()
-            .get(&DataKey::Feeder(feeder_id))
-            .unwrap();
-        feeder.is_verified = true;
-        env.storage()
-            .instance()
-            .set(&DataKey::Feeder(feeder_id), &feeder);
+            .get(&DataKey::TokenAddress)
+            .ok_or(PawChainError::NotInitialized)?;
+        Ok(token::Client::new(env, &token_address))
     }
 
-    // Get feeder profile
-    pub fn get_feeder(env: Env, feeder_id: u64) -> FeederProfile {
-        env.storage()
-            .instance()
-            .get(&DataKey::Feeder(feeder_id))
-            .unwrap()
+    // Client for the default token when taking new payments in it. Campaigns and
+    // subscriptions run in the default token, so it must still be allowlisted.
+    fn default_token_client(env: &Env) -> Result<token::Client<'_>, PawChainError> {
+        let token_client = Self::token_client(env)?;
+        Self::require_allowed_token(env, &token_client.address)?;
+        Ok(token_client)
     }
 
-    // Get feeder statistics
-    pub fn get_feeder_stats(env: Env, feeder_id: u64) -> ActivityStats {
+    fn extend_instance_ttl(env: &Env) {
         env.storage()
             .instance()
-            .get(&DataKey::FeederStats(feeder_id))
-            .unwrap()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
     }
 
-    // ========== DONATION MANAGEMENT ==========
+    // Read a persistent record, bumping its TTL if present
+    fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+        let value = env.storage().persistent().get(key);
+        if value.is_some() {
+            env.storage().persistent().extend_ttl(
+                key,
+                PERSISTENT_LIFETIME_THRESHOLD,
+                PERSISTENT_BUMP_AMOUNT,
+            );
+        }
+        value
+    }
 
-    // Record a donation
-    pub fn donate(
-        env: Env,
-        donor: Address,
-        feeder_id: u64,
-        amount: i128,
-        purpose: String,
-        dog_id: Option<u64>,
-    ) -> u64 {
-        donor.require_auth();
+    // Write a persistent record and bump its TTL
+    fn write_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
 
-        // Get token address
-        let token_address: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::TokenAddress)
-            .unwrap();
-        let token_client = token::Client::new(&env, &token_address);
+    // Collect the records with ids in [start, start + limit), skipping missing ids.
+    // `limit` is capped at MAX_PAGE_SIZE so a page never scans more than that many keys.
+    fn list_records<V: IntoVal<Env, Val> + TryFromVal<Env, Val>>(
+        env: &Env,
+        count_key: &DataKey,
+        record_key: fn(u64) -> DataKey,
+        start: u64,
+        limit: u32,
+    ) -> Vec<V> {
+        let count: u64 = env.storage().instance().get(count_key).unwrap_or(0);
+        let first = start.max(1);
+        let last = count.min(first.saturating_add(limit.min(MAX_PAGE_SIZE) as u64) - 1);
 
-        // Get feeder wallet
-        let feeder: FeederProfile = env
-            .storage()
-            .instance()
-            .get(&DataKey::Feeder(feeder_id))
-            .unwrap();
+        let mut records = Vec::new(env);
+        for id in first..=last {
+            if let Some(record) = Self::read_persistent(env, &record_key(id)) {
+                records.push_back(record);
+            }
+        }
+        records
+    }
+
+    // Load the records for `ids`, skipping any that no longer exist
+    fn load_records<V: IntoVal<Env, Val> + TryFromVal<Env, Val>>(
+        env: &Env,
+        ids: &Vec<u64>,
+        record_key: fn(u64) -> DataKey,
+    ) -> Vec<V> {
+        let mut records = Vec::new(env);
+        for id in ids.iter() {
+            if let Some(record) = Self::read_persistent(env, &record_key(id)) {
+                records.push_back(record);
+            }
+        }
+        records
+    }
+
+    fn index_len(env: &Env, index: &IndexKey) -> u64 {
+        Self::read_persistent(env, &DataKey::IndexLen(index.clone())).unwrap_or(0)
+    }
+
+    fn index_append<V: IntoVal<Env, Val> + TryFromVal<Env, Val>>(
+        env: &Env,
+        index: IndexKey,
+        id: V,
+    ) {
+        let len = Self::index_len(env, &index);
+        let bucket_key = DataKey::IndexBucket(index.clone(), len / INDEX_BUCKET_SIZE);
+        let mut bucket: Vec<V> = Self::read_persistent(env, &bucket_key).unwrap_or(Vec::new(env));
+        bucket.push_back(id);
+        Self::write_persistent(env, &bucket_key, &bucket);
+        Self::write_persistent(env, &DataKey::IndexLen(index), &(len + 1));
+    }
+
+    // Ids at positions [start, start + limit) of an index, limit capped at MAX_PAGE_SIZE
+    fn index_range<V: IntoVal<Env, Val> + TryFromVal<Env, Val>>(
+        env: &Env,
+        index: &IndexKey,
+        start: u64,
+        limit: u32,
+    ) -> Vec<V> {
+        let len = Self::index_len(env, index);
+        let end = len.min(start.saturating_add(limit.min(MAX_PAGE_SIZE) as u64));
+
+        let mut ids = Vec::new(env);
+        let mut pos = start;
+        while pos < end {
+            let bucket_no = pos / INDEX_BUCKET_SIZE;
+            let bucket_end = end.min((bucket_no + 1) * INDEX_BUCKET_SIZE);
+            let bucket: Vec<V> =
+                Self::read_persistent(env, &DataKey::IndexBucket(index.clone(), bucket_no))
+                    .unwrap_or(Vec::new(env));
+            for p in pos..bucket_end {
+                if let Some(id) = bucket.get((p % INDEX_BUCKET_SIZE) as u32) {
+                    ids.push_back(id);
+                }
+            }
+            pos = bucket_end;
+        }
+        ids
+    }
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), PawChainError> {
+        admin.require_auth();
+        let stored_admin = Self::get_admin(env.clone())?;
+        if *admin != stored_admin {
+            return Err(PawChainError::Unauthorized);
+        }
+        Ok(())
+    }
 
-        // Transfer tokens from donor to feeder
-        token_client.transfer(&donor, &feeder.wallet_address, &amount);
+    fn load_dog(env: &Env, dog_id: u64) -> Result<DogProfile, PawChainError> {
+        Self::read_persistent(env, &DataKey::Dog(dog_id)).ok_or(PawChainError::DogNotFound)
+    }
 
-        // Record donation
+    // Persist a donation whose tokens have already been transferred: updates the
+    // feeder total, the feeder and donor histories, and publishes the donation event.
+    fn record_donation(
+        env: &Env,
+        donor: Address,
+        mut feeder: FeederProfile,
+        token: Address,
+        amount: i128,
+        fee: i128,
+        purpose: String,
+        dog_id: Option<u64>,
+        escrowed: bool,
+        batch_id: Option<u64>,
+        match_of: Option<u64>,
+    ) -> Donation {
+        let feeder_id = feeder.feeder_id;
         let mut donation_count: u64 = env
             .storage()
             .instance()
@@ -364,235 +3970,623 @@ impl PawChainContract {
 
         let donation = Donation {
             donation_id: donation_count,
-            donor_address: donor,
+            donor_address: donor.clone(),
             feeder_id,
+            token: token.clone(),
             amount,
+            fee,
             timestamp: env.ledger().timestamp(),
             purpose,
             dog_id,
-            transaction_hash: String::from_str(&env, "tx_hash_placeholder"),
+            transaction_hash: String::from_str(env, "tx_hash_placeholder"),
+            escrowed,
+            batch_id,
+            match_of,
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Donation(donation_count), &donation);
+        Self::write_persistent(env, &DataKey::Donation(donation_count), &donation);
         env.storage()
             .instance()
             .set(&DataKey::DonationCount, &donation_count);
+        Self::index_append(env, IndexKey::FeederDonations(feeder_id), donation_count);
+        Self::index_append(env, IndexKey::DonorDonations(donor.clone()), donation_count);
 
-        // Update feeder total received
-        let mut feeder_data = feeder;
-        feeder_data.total_received += amount;
-        env.storage()
-            .instance()
-            .set(&DataKey::Feeder(feeder_id), &feeder_data);
+        // Update feeder total received, net of the fee
+        Self::add_amount(&mut feeder.total_received, &token, amount - fee);
+        Self::write_persistent(env, &DataKey::Feeder(feeder_id), &feeder);
 
-        donation_count
-    }
+        if let Some(dog_id) = dog_id {
+            Self::earmark_for_dog(env, dog_id, &token, amount - fee);
+            if donation.purpose == String::from_str(env, "Emergency") {
+                Self::credit_emergency(env, dog_id, &token, amount - fee);
+            }
+        }
 
-    // Get donation details
-    pub fn get_donation(env: Env, donation_id: u64) -> Donation {
-        env.storage()
-            .instance()
-            .get(&DataKey::Donation(donation_id))
-            .unwrap()
+        Self::add_to_donor_summary(
+            env,
+            donor.clone(),
+            feeder_id,
+            dog_id,
+            &token,
+            amount,
+            donation.timestamp,
+        );
+
+        DonationReceived {
+            feeder_id,
+            donor,
+            donation: donation.clone(),
+        }
+        .publish(env);
+
+        donation
     }
 
-// ========== EXPENSE TRACKING ==========
+    // Update the donor's summary, counting each feeder and dog once
+    fn add_to_donor_summary(
+        env: &Env,
+        donor: Address,
+        feeder_id: u64,
+        dog_id: Option<u64>,
+        token: &Address,
+        amount: i128,
+        timestamp: u64,
+    ) {
+        let mut summary = Self::get_donor_summary(env.clone(), donor.clone());
+        Self::add_amount(&mut summary.total_given, token, amount);
+        summary.donation_count += 1;
+        summary.last_donation = timestamp;
+        let feeder_key = DataKey::DonorFeeder(donor.clone(), feeder_id);
+        if !env.storage().persistent().has(&feeder_key) {
+            Self::write_persistent(env, &feeder_key, &true);
+            summary.feeders_supported += 1;
+        }
+        if let Some(dog_id) = dog_id {
+            let dog_key = DataKey::DonorDog(donor.clone(), dog_id);
+            if !env.storage().persistent().has(&dog_key) {
+                Self::write_persistent(env, &dog_key, &true);
+                summary.dogs_supported += 1;
+            }
+        }
+        Self::write_persistent(env, &DataKey::DonorSummary(donor), &summary);
+    }
 
-    // Record an expense
-    pub fn record_expense(
-        env: Env,
-        feeder: Address,
+    // Take the platform fee on a donation of `amount`, paying the treasury through
+    // `pay` and adding it to the fee totals. Returns the fee, 0 when none is configured.
+    fn take_fee(
+        env: &Env,
+        token: &Address,
         amount: i128,
-        category: String,
-        description: String,
-        receipt_hash: String,
-        dogs_affected: Vec<u64>,
-    ) -> u64 {
-        feeder.require_auth();
+        pay: impl FnOnce(&Address, &i128),
+    ) -> i128 {
+        let config = match Self::get_fee_config(env.clone()) {
+            Some(config) => config,
+            None => return 0,
+        };
+        let fee = amount * config.fee_bps as i128 / BPS_DENOMINATOR as i128;
+        if fee > 0 {
+            pay(&config.treasury, &fee);
+            let mut totals = Self::get_fee_totals(env.clone());
+            Self::add_amount(&mut totals, token, fee);
+            env.storage()
+                .instance()
+                .set(&DataKey::Config(ConfigKey::FeeTotals), &totals);
+        }
+        fee
+    }
 
-        let feeder_id = Self::get_feeder_id_by_address(env.clone(), feeder);
-        if feeder_id == 0 {
-            panic!("Feeder not registered");
+    // Match a direct donation from the feeder's oldest live pool in the same token.
+    // Expired and exhausted pools are dropped from the feeder's list on the way.
+    fn match_donation(env: &Env, donation: &Donation) -> Result<(), PawChainError> {
+        let key = DataKey::FeederMatchingPools(donation.feeder_id);
+        let pools: Vec<u64> = match Self::read_persistent(env, &key) {
+            Some(pools) => pools,
+            None => return Ok(()),
+        };
+        let now = env.ledger().timestamp();
+        let mut live = Vec::new(env);
+        let mut matched = false;
+        for pool_id in pools.iter() {
+            let mut pool = match Self::get_matching_pool(env.clone(), pool_id) {
+                Ok(pool) if pool.remaining > 0 && now < pool.expires_at => pool,
+                _ => continue,
+            };
+            if !matched && pool.token == donation.token {
+                let amount = (donation.amount * pool.match_bps as i128 / BPS_DENOMINATOR as i128)
+                    .min(pool.remaining);
+                if amount > 0 {
+                    pool.remaining -= amount;
+                    Self::write_persistent(env, &DataKey::MatchingPool(pool_id), &pool);
+                    let feeder = Self::load_feeder(env, donation.feeder_id)?;
+                    let token_client = token::Client::new(env, &pool.token);
+                    let contract = env.current_contract_address();
+                    let fee = Self::take_fee(env, &pool.token, amount, |treasury, fee| {
+                        token_client.transfer(&contract, treasury, fee)
+                    });
+                    token_client.transfer(&contract, &feeder.wallet_address, &(amount - fee));
+                    Self::record_donation(
+                        env,
+                        pool.sponsor.clone(),
+                        feeder,
+                        pool.token.clone(),
+                        amount,
+                        fee,
+                        String::from_str(env, "Match"),
+                        donation.dog_id,
+                        false,
+                        None,
+                        Some(donation.donation_id),
+                    );
+                    matched = true;
+                }
+            }
+            if pool.remaining > 0 {
+                live.push_back(pool_id);
+            }
+        }
+        if live.len() != pools.len() {
+            Self::write_persistent(env, &key, &live);
         }
+        Ok(())
+    }
 
-        let mut expense_count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::ExpenseCount)
-            .unwrap_or(0);
-        expense_count += 1;
+    // A dog leaving the programme ends its sponsorships without rewriting each one:
+    // a live sponsorship of a departed dog reads as ended when the dog left
+    fn sponsorship_view(mut sponsorship: Sponsorship, dog: &DogProfile) -> Sponsorship {
+        if let (true, Some(departed_at)) = (sponsorship.is_active, dog.departed_at) {
+            sponsorship.is_active = false;
+            sponsorship.ended_at = Some(departed_at);
+        }
+        sponsorship
+    }
 
-        let expense = ExpenseRecord {
-            expense_id: expense_count,
-            feeder_id,
-            amount,
-            category: category.clone(),
-            description,
-            timestamp: env.ledger().timestamp(),
-            receipt_hash,
-            dogs_affected: dogs_affected.clone(),
-            verified: false,
+    fn close_sponsorship(env: &Env, mut sponsorship: Sponsorship) {
+        sponsorship.is_active = false;
+        sponsorship.ended_at = Some(env.ledger().timestamp());
+        Self::write_persistent(
+            env,
+            &DataKey::Sponsorship(sponsorship.sponsorship_id),
+            &sponsorship,
+        );
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ActiveSponsorship(
+                sponsorship.donor.clone(),
+                sponsorship.dog_id,
+            ));
+
+        SponsorshipEnded {
+            dog_id: sponsorship.dog_id,
+            donor: sponsorship.donor.clone(),
+            sponsorship,
+        }
+        .publish(env);
+    }
+
+    // Pay out or fail a campaign whose deadline has passed
+    fn settle_campaign(env: &Env, mut campaign: Campaign) -> Result<Campaign, PawChainError> {
+        if campaign.raised >= campaign.goal {
+            let feeder = Self::load_feeder(env, campaign.feeder_id)?;
+            let token_client = Self::token_client(env)?;
+            let contract = env.current_contract_address();
+            let fee = Self::take_fee(
+                env,
+                &token_client.address,
+                campaign.raised,
+                |treasury, fee| token_client.transfer(&contract, treasury, fee),
+            );
+            token_client.transfer(&contract, &feeder.wallet_address, &(campaign.raised - fee));
+            // Contributors are recorded as donations by `record_campaign_payouts`
+            campaign.status = CampaignStatus::Successful;
+            campaign.unrecorded = campaign.raised;
+            campaign.unrecorded_fee = fee;
+        } else {
+            campaign.status = CampaignStatus::Failed;
+        }
+        Self::write_persistent(env, &DataKey::Campaign(campaign.campaign_id), &campaign);
+
+        CampaignFinalized {
+            feeder_id: campaign.feeder_id,
+            campaign_id: campaign.campaign_id,
+            campaign: campaign.clone(),
+        }
+        .publish(env);
+
+        Ok(campaign)
+    }
+
+    fn load_dog_spend(env: &Env, dog_id: u64) -> DogSpend {
+        Self::read_persistent(env, &DataKey::DogSpend(dog_id)).unwrap_or(DogSpend {
+            treatment_cost: 0,
+            expense_share: Map::new(env),
+        })
+    }
+
+    fn load_fund(env: &Env, key: &DataKey) -> Map<Address, i128> {
+        Self::read_persistent(env, key).unwrap_or(Map::new(env))
+    }
+
+    // Earmark donated funds for a dog; a dog that has left the programme passes
+    // them straight to its feeder's general fund
+    fn earmark_for_dog(env: &Env, dog_id: u64, token: &Address, amount: i128) {
+        let dog = match Self::load_dog(env, dog_id) {
+            Ok(dog) => dog,
+            Err(_) => return,
+        };
+        let key = if dog.is_active {
+            DataKey::DogFund(dog_id)
+        } else {
+            DataKey::GeneralFund(dog.feeder_id)
         };
+        let mut fund = Self::load_fund(env, &key);
+        Self::add_amount(&mut fund, token, amount);
+        Self::write_persistent(env, &key, &fund);
+    }
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Expense(expense_count), &expense);
-        env.storage()
-            .instance()
-            .set(&DataKey::ExpenseCount, &expense_count);
+    // Charge spending on a dog against its earmarked funds. Spending beyond the
+    // earmark comes out of the feeder's general pot, so the balance stops at zero.
+    // Returns the amount taken from the earmark.
+    fn draw_dog_fund(env: &Env, dog_id: u64, token: &Address, amount: i128) -> i128 {
+        let key = DataKey::DogFund(dog_id);
+        let mut fund = Self::load_fund(env, &key);
+        let balance = fund.get(token.clone()).unwrap_or(0);
+        let drawn = balance.min(amount);
+        if drawn > 0 {
+            Self::add_amount(&mut fund, token, -drawn);
+            Self::write_persistent(env, &key, &fund);
+        }
+        drawn.max(0)
+    }
 
-        // Update feeder total spent
-        let mut feeder_data: FeederProfile = env
-            .storage()
-            .instance()
-            .get(&DataKey::Feeder(feeder_id))
-            .unwrap();
-        feeder_data.total_spent += amount;
+    // Move a departing dog's earmarked funds into its feeder's general fund
+    fn release_dog_fund(env: &Env, dog: &DogProfile) {
+        let fund = Self::load_fund(env, &DataKey::DogFund(dog.dog_id));
+        if fund.is_empty() {
+            return;
+        }
+        let general_key = DataKey::GeneralFund(dog.feeder_id);
+        let mut general = Self::load_fund(env, &general_key);
+        for (token, amount) in fund.iter() {
+            Self::add_amount(&mut general, &token, amount);
+        }
+        Self::write_persistent(env, &general_key, &general);
         env.storage()
-            .instance()
-            .set(&DataKey::Feeder(feeder_id), &feeder_data);
+            .persistent()
+            .remove(&DataKey::DogFund(dog.dog_id));
 
-        // Update stats based on category
-        // --- FIX IS HERE ---
-        // We must use direct equality '==' for soroban_sdk::String
-        if category == String::from_str(&env, "Food") {
-            Self::increment_stat_by(env.clone(), feeder_id, symbol_short!("fed"), dogs_affected.len() as u64);
-        } else if category == String::from_str(&env, "Vaccination") {
-            Self::increment_stat_by(env.clone(), feeder_id, symbol_short!("vacc"), dogs_affected.len() as u64);
-        } else if category == String::from_str(&env, "Spaying") {
-            Self::increment_stat_by(env.clone(), feeder_id, symbol_short!("spayed"), dogs_affected.len() as u64);
-        } else if category == String::from_str(&env, "Neutering") {
-            Self::increment_stat_by(env.clone(), feeder_id, symbol_short!("neuter"), dogs_affected.len() as u64);
-        } else if category == String::from_str(&env, "Treatment") {
-            Self::increment_stat_by(env.clone(), feeder_id, symbol_short!("treated"), dogs_affected.len() as u64);
+        DogFundReleased {
+            feeder_id: dog.feeder_id,
+            dog_id: dog.dog_id,
+            amounts: fund,
         }
-        // --- END OF FIX ---
+        .publish(env);
+    }
 
-        expense_count
+    fn is_active_emergency(env: &Env, appeal: &EmergencyAppeal) -> bool {
+        appeal.status == EmergencyStatus::Open && env.ledger().timestamp() < appeal.deadline
     }
 
-    // Get expense details
-    pub fn get_expense(env: Env, expense_id: u64) -> ExpenseRecord {
+    fn emergency_head(env: &Env) -> u64 {
+        Self::read_persistent(env, &DataKey::EmergencyHead).unwrap_or(0)
+    }
+
+    // Move the appeal list head past appeals that have closed or expired, at most
+    // MAX_PAGE_SIZE entries per call
+    fn advance_emergency_head(env: &Env) {
+        let first = Self::emergency_head(env);
+        let mut head = first;
+        let ids = Self::index_range(env, &IndexKey::Emergencies, head, MAX_PAGE_SIZE);
+        for appeal in Self::load_records::<EmergencyAppeal>(env, &ids, DataKey::Emergency) {
+            if Self::is_active_emergency(env, &appeal) {
+                break;
+            }
+            head += 1;
+        }
+        if head != first {
+            Self::write_persistent(env, &DataKey::EmergencyHead, &head);
+        }
+    }
+
+    // Count an "Emergency" donation towards the dog's open appeal, closing it once
+    // the target is met. Appeals past their deadline are closed instead.
+    fn credit_emergency(env: &Env, dog_id: u64, token: &Address, amount: i128) {
+        let mut appeal = match Self::get_dog_emergency(env.clone(), dog_id) {
+            Some(appeal) => appeal,
+            None => return,
+        };
+        if env.ledger().timestamp() >= appeal.deadline {
+            Self::close_emergency(env, appeal, EmergencyStatus::Expired);
+            return;
+        }
+        if appeal.token != *token {
+            return;
+        }
+        appeal.raised += amount;
+        if appeal.raised >= appeal.target {
+            Self::close_emergency(env, appeal, EmergencyStatus::TargetReached);
+        } else {
+            Self::write_persistent(env, &DataKey::Emergency(appeal.appeal_id), &appeal);
+        }
+    }
+
+    fn close_emergency(env: &Env, mut appeal: EmergencyAppeal, status: EmergencyStatus) {
+        appeal.status = status;
+        appeal.closed_at = Some(env.ledger().timestamp());
+        Self::write_persistent(env, &DataKey::Emergency(appeal.appeal_id), &appeal);
         env.storage()
-            .instance()
-            .get(&DataKey::Expense(expense_id))
-            .unwrap()
+            .persistent()
+            .remove(&DataKey::DogEmergency(appeal.dog_id));
+        Self::advance_emergency_head(env);
+
+        EmergencyClosed {
+            feeder_id: appeal.feeder_id,
+            dog_id: appeal.dog_id,
+            appeal,
+        }
+        .publish(env);
     }
 
-    // ========== TREATMENT RECORDS ==========
+    fn pending_head(env: &Env) -> u64 {
+        Self::read_persistent(env, &DataKey::PendingExpensesHead).unwrap_or(0)
+    }
 
-    // Record a medical treatment
-    pub fn record_treatment(
-        env: Env,
-        feeder: Address,
-        dog_id: u64,
-        treatment_type: String,
-        description: String,
-        cost: i128,
-        veterinarian: String,
-        outcome: String,
-    ) -> u64 {
-        feeder.require_auth();
+    // Move the review queue head past expenses that have been decided, at most
+    // MAX_PAGE_SIZE entries per call
+    fn advance_pending_head(env: &Env) {
+        let first = Self::pending_head(env);
+        let mut head = first;
+        let ids = Self::index_range(env, &IndexKey::PendingExpenses, head, MAX_PAGE_SIZE);
+        for expense in Self::load_records::<ExpenseRecord>(env, &ids, DataKey::Expense) {
+            if expense.status == ExpenseStatus::Pending {
+                break;
+            }
+            head += 1;
+        }
+        if head != first {
+            Self::write_persistent(env, &DataKey::PendingExpensesHead, &head);
+        }
+    }
+
+    fn load_active_auditor(env: &Env, auditor: &Address) -> Result<Auditor, PawChainError> {
+        match Self::read_persistent::<Auditor>(
+            env,
+            &DataKey::Audit(AuditKey::Auditor(auditor.clone())),
+        ) {
+            Some(profile) if profile.is_active => Ok(profile),
+            _ => Err(PawChainError::AuditorNotFound),
+        }
+    }
+
+    fn load_feeder_audit(env: &Env, feeder_id: u64) -> FeederAuditSummary {
+        Self::read_persistent(env, &DataKey::Audit(AuditKey::FeederAudit(feeder_id))).unwrap_or(
+            FeederAuditSummary {
+                feeder_id,
+                attestations: 0,
+                distinct_auditors: 0,
+            },
+        )
+    }
 
-        let feeder_id = Self::get_feeder_id_by_address(env.clone(), feeder);
-        if feeder_id == 0 {
-            panic!("Feeder not registered");
+    // Store an attestation and update the auditor, record and feeder counts
+    fn record_attestation(
+        env: &Env,
+        auditor: Address,
+        subject: AttestationSubject,
+        feeder_id: u64,
+        note_hash: String,
+    ) -> Result<u64, PawChainError> {
+        let mut profile = Self::load_active_auditor(env, &auditor)?;
+        let attested_key = DataKey::Audit(AuditKey::Attested(subject.clone(), auditor.clone()));
+        if env.storage().persistent().has(&attested_key) {
+            return Err(PawChainError::AlreadyAttested);
         }
 
-        let mut treatment_count: u64 = env
+        let mut attestation_count: u64 = env
             .storage()
             .instance()
-            .get(&DataKey::TreatmentCount)
+            .get(&DataKey::AttestationCount)
             .unwrap_or(0);
-        treatment_count += 1;
+        attestation_count += 1;
 
-        let treatment = TreatmentRecord {
-            treatment_id: treatment_count,
-            dog_id,
+        let attestation = Attestation {
+            attestation_id: attestation_count,
+            auditor: auditor.clone(),
+            subject: subject.clone(),
             feeder_id,
-            treatment_type,
-            description,
-            cost,
-            date: env.ledger().timestamp(),
-            veterinarian,
-            outcome,
+            timestamp: env.ledger().timestamp(),
+            note_hash,
         };
 
+        Self::write_persistent(
+            env,
+            &DataKey::Audit(AuditKey::Attestation(attestation_count)),
+            &attestation,
+        );
         env.storage()
             .instance()
-            .set(&DataKey::Treatment(treatment_count), &treatment);
-        env.storage()
-            .instance()
-            .set(&DataKey::TreatmentCount, &treatment_count);
+            .set(&DataKey::AttestationCount, &attestation_count);
+        Self::write_persistent(env, &attested_key, &true);
+        Self::index_append(
+            env,
+            IndexKey::SubjectAttestations(subject),
+            attestation_count,
+        );
+
+        profile.attestation_count += 1;
+        Self::write_persistent(
+            env,
+            &DataKey::Audit(AuditKey::Auditor(auditor.clone())),
+            &profile,
+        );
+
+        // Count each auditor once per feeder
+        let mut summary = Self::load_feeder_audit(env, feeder_id);
+        summary.attestations += 1;
+        let feeder_auditor_key =
+            DataKey::Audit(AuditKey::FeederAuditor(feeder_id, auditor.clone()));
+        if !env.storage().persistent().has(&feeder_auditor_key) {
+            Self::write_persistent(env, &feeder_auditor_key, &true);
+            summary.distinct_auditors += 1;
+        }
+        Self::write_persistent(
+            env,
+            &DataKey::Audit(AuditKey::FeederAudit(feeder_id)),
+            &summary,
+        );
 
-        Self::increment_stat(env, feeder_id, symbol_short!("treated"));
+        RecordAttested {
+            feeder_id,
+            auditor,
+            attestation,
+        }
+        .publish(env);
 
-        treatment_count
+        Ok(attestation_count)
     }
 
-    // Get treatment details
-    pub fn get_treatment(env: Env, treatment_id: u64) -> TreatmentRecord {
-        env.storage()
-            .instance()
-            .get(&DataKey::Treatment(treatment_id))
-            .unwrap()
+    fn load_feeder_ledger(env: &Env, feeder_id: u64) -> FeederLedger {
+        Self::read_persistent(env, &DataKey::FeederLedger(feeder_id)).unwrap_or(FeederLedger {
+            pawchain_spent: Map::new(env),
+            external_spent: Map::new(env),
+        })
     }
 
-    // ========== HELPER FUNCTIONS ==========
+    fn charge_ledger(
+        ledger: &mut FeederLedger,
+        funding_source: FundingSource,
+        token: &Address,
+        amount: i128,
+    ) {
+        match funding_source {
+            FundingSource::PawChain => Self::add_amount(&mut ledger.pawchain_spent, token, amount),
+            FundingSource::SelfFunded | FundingSource::External => {
+                Self::add_amount(&mut ledger.external_spent, token, amount)
+            }
+        }
+    }
 
-    fn get_feeder_id_by_address(env: Env, address: Address) -> u64 {
-        let feeder_count: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::FeederCount)
-            .unwrap_or(0);
+    fn load_escrow(env: &Env, feeder_id: u64) -> Map<Address, i128> {
+        Self::read_persistent(env, &DataKey::EscrowBalance(feeder_id)).unwrap_or(Map::new(env))
+    }
 
-        for i in 1..=feeder_count {
-            let feeder: FeederProfile = env.storage().instance().get(&DataKey::Feeder(i)).unwrap();
-            if feeder.wallet_address == address {
-                return i;
+    // Add `amount` to the per-token total for `token`
+    fn add_amount(totals: &mut Map<Address, i128>, token: &Address, amount: i128) {
+        let total = totals.get(token.clone()).unwrap_or(0);
+        totals.set(token.clone(), total + amount);
+    }
+
+    // Every listed dog must be in the feeder's care and appear only once
+    fn require_distinct_dogs(
+        env: &Env,
+        dog_ids: &Vec<u64>,
+        feeder_id: u64,
+    ) -> Result<(), PawChainError> {
+        for (i, dog_id) in dog_ids.iter().enumerate() {
+            Self::load_feeder_dog(env, dog_id, feeder_id)?;
+            if dog_ids.first_index_of(dog_id) != Some(i as u32) {
+                return Err(PawChainError::DuplicateDog);
             }
         }
-        0
+        Ok(())
     }
 
-    fn increment_stat(env: Env, feeder_id: u64, stat_type: soroban_sdk::Symbol) {
-        Self::increment_stat_by(env, feeder_id, stat_type, 1);
+    // Load a dog that must be in the given feeder's care
+    fn load_feeder_dog(
+        env: &Env,
+        dog_id: u64,
+        feeder_id: u64,
+    ) -> Result<DogProfile, PawChainError> {
+        let dog = Self::load_dog(env, dog_id)?;
+        if dog.feeder_id != feeder_id {
+            return Err(PawChainError::Unauthorized);
+        }
+        Ok(dog)
     }
 
-    fn increment_stat_by(env: Env, feeder_id: u64, stat_type: soroban_sdk::Symbol, count: u64) {
-        let mut stats: ActivityStats = env
-            .storage()
-            .instance()
-            .get(&DataKey::FeederStats(feeder_id))
-            .unwrap();
-
-        // --- FIX IS HERE ---
-        // Instead of converting to a string, compare the symbols directly.
-        // This is much more efficient and idiomatic for Soroban.
-        if stat_type == symbol_short!("fed") {
-            stats.dogs_fed += count;
-        } else if stat_type == symbol_short!("vacc") {
-            stats.dogs_vaccinated += count;
-        } else if stat_type == symbol_short!("spayed") {
-            stats.dogs_spayed += count;
-        } else if stat_type == symbol_short!("neuter") {
-            stats.dogs_neutered += count;
-        } else if stat_type == symbol_short!("treated") {
-            stats.dogs_treated += count;
-        } else if stat_type == symbol_short!("rescued") {
-            stats.dogs_rescued += count;
-        } else if stat_type == symbol_short!("adopted") {
-            stats.dogs_adopted += count;
-        }
-        // --- END OF FIX ---
+    fn load_feeder(env: &Env, feeder_id: u64) -> Result<FeederProfile, PawChainError> {
+        Self::read_persistent(env, &DataKey::Feeder(feeder_id)).ok_or(PawChainError::FeederNotFound)
+    }
+
+    fn load_vet(env: &Env, vet_id: u64) -> Result<VetProfile, PawChainError> {
+        Self::read_persistent(env, &DataKey::Vet(VetKey::Profile(vet_id)))
+            .ok_or(PawChainError::VetNotFound)
+    }
+
+    fn get_feeder_id_by_address(env: &Env, address: &Address) -> Result<u64, PawChainError> {
+        Self::read_persistent(env, &DataKey::FeederByAddress(address.clone()))
+            .ok_or(PawChainError::FeederNotFound)
+    }
+
+    fn increment_stat(
+        env: &Env,
+        feeder_id: u64,
+        counter: ActivityCounter,
+    ) -> Result<(), PawChainError> {
+        Self::increment_stat_by(env, feeder_id, counter, 1)
+    }
+
+    fn increment_stat_by(
+        env: &Env,
+        feeder_id: u64,
+        counter: ActivityCounter,
+        count: u64,
+    ) -> Result<(), PawChainError> {
+        Self::update_stat(env, feeder_id, counter, |value| *value += count)
+    }
+
+    fn decrement_stat_by(
+        env: &Env,
+        feeder_id: u64,
+        counter: ActivityCounter,
+        count: u64,
+    ) -> Result<(), PawChainError> {
+        Self::update_stat(env, feeder_id, counter, |value| {
+            *value = value.saturating_sub(count)
+        })
+    }
+
+    fn update_stat(
+        env: &Env,
+        feeder_id: u64,
+        counter: ActivityCounter,
+        update: impl FnOnce(&mut u64),
+    ) -> Result<(), PawChainError> {
+        let mut stats: ActivityStats = Self::read_persistent(env, &DataKey::FeederStats(feeder_id))
+            .ok_or(PawChainError::FeederNotFound)?;
+
+        update(match counter {
+            ActivityCounter::Fed => &mut stats.dogs_fed,
+            ActivityCounter::Vaccinated => &mut stats.dogs_vaccinated,
+            ActivityCounter::Spayed => &mut stats.dogs_spayed,
+            ActivityCounter::Neutered => &mut stats.dogs_neutered,
+            ActivityCounter::Treated => &mut stats.dogs_treated,
+            ActivityCounter::Rescued => &mut stats.dogs_rescued,
+            ActivityCounter::Adopted => &mut stats.dogs_adopted,
+        });
 
         stats.last_updated = env.ledger().timestamp();
-        env.storage()
-            .instance()
-            .set(&DataKey::FeederStats(feeder_id), &stats);
+        Self::write_persistent(env, &DataKey::FeederStats(feeder_id), &stats);
+        Ok(())
+    }
+
+    // Activity counter an expense category increments, if any. Custom categories
+    // must be registered.
+    fn category_counter(
+        env: &Env,
+        category: &ExpenseCategory,
+    ) -> Result<Option<ActivityCounter>, PawChainError> {
+        Ok(match category {
+            ExpenseCategory::Food => Some(ActivityCounter::Fed),
+            ExpenseCategory::Vaccination => Some(ActivityCounter::Vaccinated),
+            ExpenseCategory::Spaying => Some(ActivityCounter::Spayed),
+            ExpenseCategory::Neutering => Some(ActivityCounter::Neutered),
+            ExpenseCategory::Treatment | ExpenseCategory::Medicine => {
+                Some(ActivityCounter::Treated)
+            }
+            ExpenseCategory::Custom(name) => Self::get_custom_categories(env.clone())
+                .get(name.clone())
+                .ok_or(PawChainError::UnknownCategory)?,
+        })
     }
 
     // Get total counts
@@ -624,3 +4618,5 @@ impl PawChainContract {
             .unwrap_or(0)
     }
 }
+
+mod test;
@@ -3,32 +3,35 @@ extern crate std;
 
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as _, Ledger as _},
-    Env, String, Vec,
+    testutils::{storage::Persistent as _, Address as _, Events as _, Ledger as _},
+    Env, String, Symbol, Vec,
 };
 
 // Helper function to set up the test environment
-fn setup_test() -> (
+fn setup_test<'a>() -> (
     Env,
-    PawChainContractClient,
+    PawChainContractClient<'a>,
     Address,
     Address,
-    token::Client,
+    token::StellarAssetClient<'a>,
 ) {
     let env = Env::default();
+    env.mock_all_auths();
     env.ledger().set_timestamp(123456789);
 
     // Register the PawChain contract
-    let contract_id = env.register_contract(None, PawChainContract);
+    let contract_id = env.register(PawChainContract, ());
     let client = PawChainContractClient::new(&env, &contract_id);
 
     // Create an admin user
-    let admin = Address::random(&env);
+    let admin = Address::generate(&env);
 
     // Set up a mock token contract
-    let token_admin = Address::random(&env);
-    let token_id = env.register_stellar_asset_contract(token_admin);
-    let token_client = token::Client::new(&env, &token_id);
+    let token_admin = Address::generate(&env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = token::StellarAssetClient::new(&env, &token_id);
 
     // Initialize the PawChain contract
     client.initialize(&admin, &token_id);
@@ -40,16 +43,16 @@ fn setup_test() -> (
 fn test_initialize() {
     // --- Arrange ---
     let env = Env::default();
-    let contract_id = env.register_contract(None, PawChainContract);
+    let contract_id = env.register(PawChainContract, ());
     let client = PawChainContractClient::new(&env, &contract_id);
-    let admin = Address::random(&env);
-    let token_id = Address::random(&env); // Just need an address for this test
+    let admin = Address::generate(&env);
+    let token_id = Address::generate(&env); // Just need an address for this test
 
     // --- Act ---
     client.initialize(&admin, &token_id);
 
     // --- Assert ---
-    // We can't directly read storage, but we can test the "panic if already initialized"
+    // We can't directly read storage, but we can test the "already initialized" error
     // which proves *something* was set.
     // We'll also test the getters for initial counts.
     assert_eq!(client.get_total_dogs(), 0);
@@ -58,22 +61,24 @@ fn test_initialize() {
 }
 
 #[test]
-#[should_panic(expected = "Already initialized")]
 fn test_initialize_twice() {
     // --- Arrange ---
-    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (_env, client, admin, token_id, _token_client) = setup_test();
 
-    // --- Act ---
+    // --- Act & Assert ---
     // The contract was already initialized in setup_test()
-    // Calling it again should panic.
-    client.initialize(&admin, &token_id);
+    // Calling it again should fail.
+    assert_eq!(
+        client.try_initialize(&admin, &token_id),
+        Err(Ok(PawChainError::AlreadyInitialized))
+    );
 }
 
 #[test]
 fn test_feeder_management() {
     // --- Arrange ---
     let (env, client, admin, _token_id, _token_client) = setup_test();
-    let feeder_addr = Address::random(&env);
+    let feeder_addr = Address::generate(&env);
 
     // --- Act ---
     let feeder_id = client.register_feeder(
@@ -92,15 +97,15 @@ fn test_feeder_management() {
     let profile = client.get_feeder(&feeder_id);
     assert_eq!(profile.name, String::from_str(&env, "Happy Paws NGO"));
     assert_eq!(profile.wallet_address, feeder_addr);
-    assert_eq!(profile.is_verified, false);
-    assert_eq!(profile.total_received, 0);
+    assert!(!profile.is_verified);
+    assert!(profile.total_received.is_empty());
 
     // --- Act (Verify) ---
     client.verify_feeder(&admin, &feeder_id);
 
     // --- Assert (Verify) ---
     let verified_profile = client.get_feeder(&feeder_id);
-    assert_eq!(verified_profile.is_verified, true);
+    assert!(verified_profile.is_verified);
 
     // --- Assert (Stats) ---
     let stats = client.get_feeder_stats(&feeder_id);
@@ -109,12 +114,11 @@ fn test_feeder_management() {
 }
 
 #[test]
-#[should_panic(expected = "Unauthorized")]
 fn test_verify_feeder_unauthorized() {
     // --- Arrange ---
     let (env, client, _admin, _token_id, _token_client) = setup_test();
-    let feeder_addr = Address::random(&env);
-    let imposter_addr = Address::random(&env);
+    let feeder_addr = Address::generate(&env);
+    let imposter_addr = Address::generate(&env);
 
     let feeder_id = client.register_feeder(
         &feeder_addr,
@@ -127,14 +131,17 @@ fn test_verify_feeder_unauthorized() {
 
     // --- Act & Assert ---
     // Imposter tries to verify
-    client.verify_feeder(&imposter_addr, &feeder_id);
+    assert_eq!(
+        client.try_verify_feeder(&imposter_addr, &feeder_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
 }
 
 #[test]
 fn test_dog_management() {
     // --- Arrange ---
     let (env, client, _admin, _token_id, _token_client) = setup_test();
-    let feeder_addr = Address::random(&env);
+    let feeder_addr = Address::generate(&env);
     let feeder_id = client.register_feeder(
         &feeder_addr,
         &String::from_str(&env, "Happy Paws NGO"),
@@ -162,7 +169,7 @@ fn test_dog_management() {
     let dog = client.get_dog(&dog_id);
     assert_eq!(dog.name, String::from_str(&env, "Buddy"));
     assert_eq!(dog.feeder_id, feeder_id);
-    assert_eq!(dog.is_active, true);
+    assert!(dog.is_active);
 
     // Check if feeder stats were updated
     let stats = client.get_feeder_stats(&feeder_id);
@@ -183,22 +190,18 @@ fn test_dog_management() {
         updated_dog.health_status,
         String::from_str(&env, "Recovering")
     );
-    assert_eq!(
-        updated_dog.sickness,
-        String::from_str(&env, "Kennel Cough")
-    );
+    assert_eq!(updated_dog.sickness, String::from_str(&env, "Kennel Cough"));
     assert_eq!(updated_dog.last_updated, 123456999);
 }
 
 #[test]
-#[should_panic(expected = "Feeder not registered")]
 fn test_register_dog_unregistered_feeder() {
     // --- Arrange ---
     let (env, client, _admin, _token_id, _token_client) = setup_test();
-    let unregistered_addr = Address::random(&env);
+    let unregistered_addr = Address::generate(&env);
 
     // --- Act & Assert ---
-    client.register_dog(
+    let result = client.try_register_dog(
         &unregistered_addr,
         &String::from_str(&env, "Ghost Dog"),
         &1,
@@ -207,16 +210,16 @@ fn test_register_dog_unregistered_feeder() {
         &String::from_str(&env, "Sick"),
         &String::from_str(&env, "Malnutrition"),
     );
+    assert_eq!(result, Err(Ok(PawChainError::FeederNotFound)));
 }
 
 #[test]
-#[should_panic(expected = "Unauthorized")]
 fn test_update_dog_wrong_feeder() {
     // --- Arrange ---
     let (env, client, _admin, _token_id, _token_client) = setup_test();
 
     // Feeder 1
-    let feeder1_addr = Address::random(&env);
+    let feeder1_addr = Address::generate(&env);
     client.register_feeder(
         &feeder1_addr,
         &String::from_str(&env, "Feeder One"),
@@ -227,7 +230,7 @@ fn test_update_dog_wrong_feeder() {
     );
 
     // Feeder 2
-    let feeder2_addr = Address::random(&env);
+    let feeder2_addr = Address::generate(&env);
     client.register_feeder(
         &feeder2_addr,
         &String::from_str(&env, "Feeder Two"),
@@ -250,20 +253,22 @@ fn test_update_dog_wrong_feeder() {
 
     // --- Act & Assert ---
     // Feeder 2 tries to update Feeder 1's dog
-    client.update_dog_health(
+    let result = client.try_update_dog_health(
         &feeder2_addr,
         &dog_id,
         &String::from_str(&env, "Sick"),
         &String::from_str(&env, "Stolen"),
     );
+    assert_eq!(result, Err(Ok(PawChainError::Unauthorized)));
 }
 
 #[test]
 fn test_donation_flow() {
     // --- Arrange ---
-    let (env, client, admin, _token_id, token_client) = setup_test();
-    let feeder_addr = Address::random(&env);
-    let donor_addr = Address::random(&env);
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let feeder_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
 
     // Register and verify feeder
     let feeder_id = client.register_feeder(
@@ -278,13 +283,14 @@ fn test_donation_flow() {
 
     // Mint tokens to donor
     token_client.mint(&donor_addr, &10000);
-    assert_eq!(token_client.balance(&donor_addr), 10000);
-    assert_eq!(token_client.balance(&feeder_addr), 0);
+    assert_eq!(token.balance(&donor_addr), 10000);
+    assert_eq!(token.balance(&feeder_addr), 0);
 
     // --- Act ---
     let donation_id = client.donate(
         &donor_addr,
         &feeder_id,
+        &token_id,
         &7500,
         &String::from_str(&env, "General Fund"),
         &None, // No specific dog
@@ -301,20 +307,23 @@ fn test_donation_flow() {
     assert_eq!(donation.dog_id, None);
 
     // Check token balances
-    assert_eq!(token_client.balance(&donor_addr), 2500);
-    assert_eq!(token_client.balance(&feeder_addr), 7500);
+    assert_eq!(token.balance(&donor_addr), 2500);
+    assert_eq!(token.balance(&feeder_addr), 7500);
 
     // Check feeder profile
     let feeder_profile = client.get_feeder(&feeder_id);
-    assert_eq!(feeder_profile.total_received, 7500);
-    assert_eq!(feeder_profile.total_spent, 0);
+    assert_eq!(
+        feeder_profile.total_received.get(token_id.clone()),
+        Some(7500)
+    );
+    assert!(feeder_profile.total_spent.is_empty());
 }
 
 #[test]
 fn test_expense_and_stats_tracking() {
     // --- Arrange ---
-    let (env, client, _admin, _token_id, _token_client) = setup_test();
-    let feeder_addr = Address::random(&env);
+    let (env, client, _admin, token_id, _token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
     let feeder_id = client.register_feeder(
         &feeder_addr,
         &String::from_str(&env, "Feeder"),
@@ -356,8 +365,10 @@ fn test_expense_and_stats_tracking() {
 
     let expense1_id = client.record_expense(
         &feeder_addr,
+        &token_id,
         &1000,
-        &String::from_str(&env, "Food"),
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
         &String::from_str(&env, "Dog food bags"),
         &String::from_str(&env, "ipfs://hash1"),
         &food_dogs,
@@ -368,7 +379,7 @@ fn test_expense_and_stats_tracking() {
     assert_eq!(client.get_total_expenses(), 1);
 
     let feeder_profile = client.get_feeder(&feeder_id);
-    assert_eq!(feeder_profile.total_spent, 1000);
+    assert_eq!(feeder_profile.total_spent.get(token_id.clone()), Some(1000));
 
     let stats_after_food = client.get_feeder_stats(&feeder_id);
     assert_eq!(stats_after_food.dogs_fed, 2); // Incremented by 2
@@ -380,8 +391,10 @@ fn test_expense_and_stats_tracking() {
 
     let expense2_id = client.record_expense(
         &feeder_addr,
+        &token_id,
         &300,
-        &String::from_str(&env, "Vaccination"),
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Vaccination,
         &String::from_str(&env, "Annual shots"),
         &String::from_str(&env, "ipfs://hash2"),
         &vacc_dogs,
@@ -390,7 +403,10 @@ fn test_expense_and_stats_tracking() {
     // --- Assert (Vaccination Expense) ---
     assert_eq!(expense2_id, 2);
     let feeder_profile_2 = client.get_feeder(&feeder_id);
-    assert_eq!(feeder_profile_2.total_spent, 1300); // 1000 + 300
+    assert_eq!(
+        feeder_profile_2.total_spent.get(token_id.clone()),
+        Some(1300)
+    ); // 1000 + 300
 
     let stats_after_vacc = client.get_feeder_stats(&feeder_id);
     assert_eq!(stats_after_vacc.dogs_fed, 2); // Unchanged
@@ -404,7 +420,7 @@ fn test_expense_and_stats_tracking() {
 fn test_treatment_record() {
     // --- Arrange ---
     let (env, client, _admin, _token_id, _token_client) = setup_test();
-    let feeder_addr = Address::random(&env);
+    let feeder_addr = Address::generate(&env);
     let feeder_id = client.register_feeder(
         &feeder_addr,
         &String::from_str(&env, "Feeder"),
@@ -435,7 +451,10 @@ fn test_treatment_record() {
         &String::from_str(&env, "Parvovirus Treatment"),
         &String::from_str(&env, "IV fluids and medication"),
         &500,
+        &FundingSource::SelfFunded,
         &String::from_str(&env, "Dr. Vet"),
+        &None,
+        &false,
         &String::from_str(&env, "Ongoing"),
     );
 
@@ -444,13 +463,3432 @@ fn test_treatment_record() {
     let treatment = client.get_treatment(&treatment_id);
     assert_eq!(treatment.dog_id, dog_id);
     assert_eq!(treatment.cost, 500);
-    assert_eq!(
-        treatment.veterinarian,
-        String::from_str(&env, "Dr. Vet")
-    );
+    assert_eq!(treatment.veterinarian, String::from_str(&env, "Dr. Vet"));
 
     // Check if stats were updated
     let stats_after = client.get_feeder_stats(&feeder_id);
     assert_eq!(stats_after.dogs_rescued, 1);
     assert_eq!(stats_after.dogs_treated, 1); // Incremented
-}
\ No newline at end of file
+}
+#[test]
+fn test_missing_records_return_errors() {
+    // --- Arrange ---
+    let (_env, client, _admin, _token_id, _token_client) = setup_test();
+
+    // --- Act & Assert ---
+    assert_eq!(client.try_get_dog(&42), Err(Ok(PawChainError::DogNotFound)));
+    assert_eq!(
+        client.try_get_feeder(&42),
+        Err(Ok(PawChainError::FeederNotFound))
+    );
+    assert_eq!(
+        client.try_get_feeder_stats(&42),
+        Err(Ok(PawChainError::FeederNotFound))
+    );
+    assert_eq!(
+        client.try_get_donation(&42),
+        Err(Ok(PawChainError::DonationNotFound))
+    );
+    assert_eq!(
+        client.try_get_expense(&42),
+        Err(Ok(PawChainError::ExpenseNotFound))
+    );
+    assert_eq!(
+        client.try_get_treatment(&42),
+        Err(Ok(PawChainError::TreatmentNotFound))
+    );
+    assert_eq!(
+        client.try_get_attestation(&42),
+        Err(Ok(PawChainError::AttestationNotFound))
+    );
+}
+
+#[test]
+fn test_donate_invalid_amount() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, _token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+
+    // --- Act & Assert ---
+    let result = client.try_donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &0,
+        &String::from_str(&env, "General Fund"),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::InvalidAmount)));
+
+    // Unknown feeder
+    let result = client.try_donate(
+        &donor_addr,
+        &99,
+        &token_id,
+        &100,
+        &String::from_str(&env, "General Fund"),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::FeederNotFound)));
+}
+
+#[test]
+fn test_register_feeder_twice_rejected() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+
+    // --- Act & Assert ---
+    let result = client.try_register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws Again"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+    assert_eq!(result, Err(Ok(PawChainError::FeederAlreadyRegistered)));
+    assert_eq!(client.get_total_feeders(), 1);
+}
+
+#[test]
+fn test_records_use_persistent_storage_with_ttl() {
+    // --- Arrange ---
+    let (env, client, admin, _token_id, _token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+
+    // --- Assert (storage placement) ---
+    env.as_contract(&client.address, || {
+        let key = DataKey::Feeder(feeder_id);
+        assert!(env.storage().persistent().has(&key));
+        assert!(!env.storage().instance().has(&key));
+        assert!(env.storage().persistent().get_ttl(&key) >= PERSISTENT_LIFETIME_THRESHOLD);
+        assert!(env.storage().instance().has(&DataKey::FeederCount));
+    });
+
+    // --- Act (bulk bump after time passes) ---
+    env.ledger()
+        .with_mut(|li| li.sequence_number += 10 * DAY_IN_LEDGERS);
+    let ttl_before = env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&DataKey::Feeder(feeder_id))
+    });
+    let mut keys = Vec::new(&env);
+    keys.push_back(DataKey::Feeder(feeder_id));
+    keys.push_back(DataKey::FeederStats(feeder_id));
+    keys.push_back(DataKey::Dog(99)); // missing keys are skipped
+    client.extend_ttl(&admin, &keys);
+
+    // --- Assert (bump) ---
+    env.as_contract(&client.address, || {
+        let ttl_after = env
+            .storage()
+            .persistent()
+            .get_ttl(&DataKey::Feeder(feeder_id));
+        assert!(ttl_after > ttl_before);
+        assert_eq!(ttl_after, PERSISTENT_BUMP_AMOUNT);
+    });
+
+    // Only the admin may trigger bulk bumps
+    let imposter = Address::generate(&env);
+    assert_eq!(
+        client.try_extend_ttl(&imposter, &keys),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_mutations_publish_events() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &1000);
+
+    // --- Act & Assert (feeder registration) ---
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+    let (contract, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(contract, client.address);
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "feeder_registered"),
+            feeder_id,
+            feeder_addr.clone()
+        )
+            .into_val(&env)
+    );
+    assert_eq!(
+        FeederProfile::try_from_val(&env, &data).unwrap(),
+        client.get_feeder(&feeder_id)
+    );
+
+    // --- Act & Assert (verification) ---
+    client.verify_feeder(&admin, &feeder_id);
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "feeder_verified"),
+            feeder_id,
+            admin.clone()
+        )
+            .into_val(&env)
+    );
+
+    // --- Act & Assert (dog registration and health update) ---
+    let dog_id = client.register_dog(
+        &feeder_addr,
+        &String::from_str(&env, "Buddy"),
+        &2,
+        &String::from_str(&env, "Indie"),
+        &String::from_str(&env, "Street"),
+        &String::from_str(&env, "Healthy"),
+        &String::from_str(&env, "None"),
+    );
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "dog_registered"), feeder_id, dog_id).into_val(&env)
+    );
+
+    client.update_dog_health(
+        &feeder_addr,
+        &dog_id,
+        &String::from_str(&env, "Sick"),
+        &String::from_str(&env, "Mange"),
+    );
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "dog_health"), feeder_id, dog_id).into_val(&env)
+    );
+    assert_eq!(
+        DogProfile::try_from_val(&env, &data).unwrap().health_status,
+        String::from_str(&env, "Sick")
+    );
+
+    // --- Act & Assert (donation) ---
+    let donation_id = client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &400,
+        &String::from_str(&env, "Specific Dog"),
+        &Some(dog_id),
+    );
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "donation"), feeder_id, donor_addr.clone()).into_val(&env)
+    );
+    assert_eq!(
+        Donation::try_from_val(&env, &data).unwrap(),
+        client.get_donation(&donation_id)
+    );
+
+    // --- Act & Assert (expense and treatment) ---
+    let mut dogs = Vec::new(&env);
+    dogs.push_back(dog_id);
+    let expense_id = client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &100,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://receipt"),
+        &dogs,
+    );
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "expense"), feeder_id, expense_id).into_val(&env)
+    );
+
+    client.record_treatment(
+        &feeder_addr,
+        &dog_id,
+        &String::from_str(&env, "Skin"),
+        &String::from_str(&env, "Medicated bath"),
+        &50,
+        &FundingSource::SelfFunded,
+        &String::from_str(&env, "Dr. Vet"),
+        &None,
+        &false,
+        &String::from_str(&env, "Ongoing"),
+    );
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "treatment"), feeder_id, dog_id).into_val(&env)
+    );
+}
+
+#[test]
+fn test_upgrade_and_migrate_are_admin_gated() {
+    // --- Arrange ---
+    let (env, client, admin, _token_id, _token_client) = setup_test();
+    let imposter = Address::generate(&env);
+    let wasm_hash = BytesN::from_array(&env, &[7u8; 32]);
+
+    // --- Assert ---
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+    assert_eq!(
+        client.try_upgrade(&imposter, &wasm_hash),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&imposter, &10),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+
+    // A freshly initialized contract is already on the current schema
+    assert_eq!(
+        client.try_migrate(&admin, &10),
+        Err(Ok(PawChainError::SchemaUpToDate))
+    );
+
+    // Storage from a newer schema has no migration path
+    env.as_contract(&client.address, || {
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &(SCHEMA_VERSION + 1));
+    });
+    assert_eq!(
+        client.try_migrate(&admin, &10),
+        Err(Ok(PawChainError::SchemaNotMigratable))
+    );
+}
+
+#[test]
+fn test_two_step_admin_handover() {
+    // --- Arrange ---
+    let (env, client, admin, _token_id, _token_client) = setup_test();
+    let new_admin = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    assert_eq!(client.get_admin(), admin);
+
+    // Nothing to accept yet
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(PawChainError::NoPendingAdmin))
+    );
+
+    // Only the current admin may propose
+    assert_eq!(
+        client.try_propose_admin(&stranger, &new_admin),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+
+    // --- Act (propose) ---
+    client.propose_admin(&admin, &new_admin);
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "admin_proposed"), admin.clone()).into_val(&env)
+    );
+
+    // Proposal alone does not change the admin, and only the nominee can accept
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(
+        client.try_accept_admin(&stranger),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+
+    // --- Act (accept) ---
+    client.accept_admin(&new_admin);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "admin_changed"), admin.clone()).into_val(&env)
+    );
+    assert_eq!(Address::try_from_val(&env, &data).unwrap(), new_admin);
+
+    // --- Assert ---
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(PawChainError::NoPendingAdmin))
+    );
+
+    // Old admin has lost its privileges
+    let feeder_addr = Address::generate(&env);
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+    assert_eq!(
+        client.try_verify_feeder(&admin, &feeder_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    client.verify_feeder(&new_admin, &feeder_id);
+    assert!(client.get_feeder(&feeder_id).is_verified);
+}
+
+#[test]
+fn test_paginated_lists() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+    for _ in 0..5 {
+        client.register_dog(
+            &feeder_addr,
+            &String::from_str(&env, "Pup"),
+            &1,
+            &String::from_str(&env, "Indie"),
+            &String::from_str(&env, "Street"),
+            &String::from_str(&env, "Healthy"),
+            &String::from_str(&env, "None"),
+        );
+    }
+    client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &100,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    let mut dogs = Vec::new(&env);
+    dogs.push_back(1u64);
+    client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &50,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://receipt"),
+        &dogs,
+    );
+    client.record_treatment(
+        &feeder_addr,
+        &2,
+        &String::from_str(&env, "Checkup"),
+        &String::from_str(&env, "General exam"),
+        &0,
+        &FundingSource::SelfFunded,
+        &String::from_str(&env, "Dr. Vet"),
+        &None,
+        &false,
+        &String::from_str(&env, "Successful"),
+    );
+
+    // --- Assert (pages) ---
+    let page = client.list_dogs(&1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().dog_id, 1);
+    assert_eq!(page.get(1).unwrap().dog_id, 2);
+
+    let page = client.list_dogs(&4, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().dog_id, 4);
+
+    // start 0 is treated as the first id; past-the-end and zero limits are empty
+    assert_eq!(client.list_dogs(&0, &3).len(), 3);
+    assert_eq!(client.list_dogs(&6, &10).len(), 0);
+    assert_eq!(client.list_dogs(&1, &0).len(), 0);
+
+    assert_eq!(client.list_feeders(&1, &10).len(), 1);
+    assert_eq!(client.list_donations(&1, &10).get(0).unwrap().amount, 100);
+    assert_eq!(client.list_expenses(&1, &10).get(0).unwrap().amount, 50);
+    assert_eq!(client.list_treatments(&1, &10).get(0).unwrap().dog_id, 2);
+
+    // --- Assert (gaps are skipped) ---
+    env.as_contract(&client.address, || {
+        env.storage().persistent().remove(&DataKey::Dog(3));
+    });
+    let page = client.list_dogs(&1, &10);
+    assert_eq!(page.len(), 4);
+    assert_eq!(page.get(2).unwrap().dog_id, 4);
+}
+
+#[test]
+fn test_list_page_size_is_capped() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    for _ in 0..(MAX_PAGE_SIZE + 5) {
+        client.register_feeder(
+            &Address::generate(&env),
+            &String::from_str(&env, "Feeder"),
+            &String::from_str(&env, "Individual"),
+            &String::from_str(&env, "City"),
+            &String::from_str(&env, "N/A"),
+            &String::from_str(&env, "feeder@mail.com"),
+        );
+    }
+
+    // --- Act & Assert ---
+    assert_eq!(client.list_feeders(&1, &u32::MAX).len(), MAX_PAGE_SIZE);
+    assert_eq!(
+        client
+            .list_feeders(&(MAX_PAGE_SIZE as u64 + 1), &u32::MAX)
+            .len(),
+        5
+    );
+}
+
+#[test]
+fn test_feeder_secondary_indexes() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let feeder1_addr = Address::generate(&env);
+    let feeder2_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let feeder1_id = client.register_feeder(
+        &feeder1_addr,
+        &String::from_str(&env, "Feeder One"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "NY"),
+        &String::from_str(&env, "1"),
+        &String::from_str(&env, "1"),
+    );
+    let feeder2_id = client.register_feeder(
+        &feeder2_addr,
+        &String::from_str(&env, "Feeder Two"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "LA"),
+        &String::from_str(&env, "2"),
+        &String::from_str(&env, "2"),
+    );
+
+    // Interleave records of both feeders
+    for i in 0..6u32 {
+        let feeder_addr = if i % 2 == 0 {
+            &feeder1_addr
+        } else {
+            &feeder2_addr
+        };
+        let feeder_id = if i % 2 == 0 { feeder1_id } else { feeder2_id };
+        client.register_dog(
+            feeder_addr,
+            &String::from_str(&env, "Pup"),
+            &i,
+            &String::from_str(&env, "Indie"),
+            &String::from_str(&env, "Street"),
+            &String::from_str(&env, "Healthy"),
+            &String::from_str(&env, "None"),
+        );
+        client.donate(
+            &donor_addr,
+            &feeder_id,
+            &token_id,
+            &(100 + i as i128),
+            &String::from_str(&env, "General"),
+            &None,
+        );
+        client.record_expense(
+            feeder_addr,
+            &token_id,
+            &(10 + i as i128),
+            &FundingSource::SelfFunded,
+            &ExpenseCategory::Food,
+            &String::from_str(&env, "Kibble"),
+            &String::from_str(&env, "ipfs://receipt"),
+            &Vec::new(&env),
+        );
+    }
+
+    // --- Assert ---
+    let dogs = client.get_feeder_dogs(&feeder1_id, &0, &10);
+    assert_eq!(dogs.len(), 3);
+    for dog in dogs.iter() {
+        assert_eq!(dog.feeder_id, feeder1_id);
+    }
+    let page = client.get_feeder_dogs(&feeder2_id, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().dog_id, 4);
+
+    let donations = client.get_feeder_donations(&feeder2_id, &0, &10);
+    assert_eq!(donations.len(), 3);
+    assert_eq!(donations.get(0).unwrap().amount, 101);
+    assert_eq!(donations.get(2).unwrap().amount, 105);
+
+    let expenses = client.get_feeder_expenses(&feeder1_id, &2, &10);
+    assert_eq!(expenses.len(), 1);
+    assert_eq!(expenses.get(0).unwrap().amount, 14);
+
+    assert_eq!(client.get_feeder_dogs(&feeder1_id, &3, &10).len(), 0);
+    assert_eq!(
+        client.try_get_feeder_dogs(&99, &0, &10),
+        Err(Ok(PawChainError::FeederNotFound))
+    );
+}
+
+#[test]
+fn test_index_spans_buckets() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    let index = IndexKey::FeederDogs(1);
+    let total = INDEX_BUCKET_SIZE * 2 + 5;
+
+    // --- Act ---
+    env.as_contract(&client.address, || {
+        for id in 1..=total {
+            PawChainContract::index_append(&env, index.clone(), id);
+        }
+    });
+
+    // --- Assert ---
+    env.as_contract(&client.address, || {
+        assert_eq!(PawChainContract::index_len(&env, &index), total);
+        let ids: Vec<u64> = PawChainContract::index_range(&env, &index, INDEX_BUCKET_SIZE - 2, 5);
+        assert_eq!(ids.len(), 5);
+        assert_eq!(ids.get(0).unwrap(), INDEX_BUCKET_SIZE - 1);
+        assert_eq!(ids.get(4).unwrap(), INDEX_BUCKET_SIZE + 3);
+        let tail: Vec<u64> = PawChainContract::index_range(&env, &index, total - 3, 10);
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail.get(2).unwrap(), total);
+    });
+}
+
+#[test]
+fn test_dog_medical_history() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, _token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Feeder"),
+        &String::from_str(&env, "Individual"),
+        &String::from_str(&env, "City"),
+        &String::from_str(&env, "N/A"),
+        &String::from_str(&env, "feeder@mail.com"),
+    );
+    let dog1_id = client.register_dog(
+        &feeder_addr,
+        &String::from_str(&env, "Buddy"),
+        &3,
+        &String::from_str(&env, "Lab"),
+        &String::from_str(&env, "Shelter"),
+        &String::from_str(&env, "Sick"),
+        &String::from_str(&env, "Parvovirus"),
+    );
+    let dog2_id = client.register_dog(
+        &feeder_addr,
+        &String::from_str(&env, "Max"),
+        &4,
+        &String::from_str(&env, "Indie"),
+        &String::from_str(&env, "Shelter"),
+        &String::from_str(&env, "Healthy"),
+        &String::from_str(&env, "None"),
+    );
+
+    // --- Act ---
+    client.record_treatment(
+        &feeder_addr,
+        &dog1_id,
+        &String::from_str(&env, "Parvovirus Treatment"),
+        &String::from_str(&env, "IV fluids"),
+        &500,
+        &FundingSource::SelfFunded,
+        &String::from_str(&env, "Dr. Vet"),
+        &None,
+        &false,
+        &String::from_str(&env, "Ongoing"),
+    );
+    client.record_treatment(
+        &feeder_addr,
+        &dog2_id,
+        &String::from_str(&env, "Checkup"),
+        &String::from_str(&env, "Annual exam"),
+        &80,
+        &FundingSource::SelfFunded,
+        &String::from_str(&env, "Dr. Vet"),
+        &None,
+        &false,
+        &String::from_str(&env, "Successful"),
+    );
+    let mut both = Vec::new(&env);
+    both.push_back(dog1_id);
+    both.push_back(dog2_id);
+    client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &1000,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Dog food bags"),
+        &String::from_str(&env, "ipfs://hash1"),
+        &both,
+    );
+    let mut only_dog1 = Vec::new(&env);
+    only_dog1.push_back(dog1_id);
+    client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &300,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Vaccination,
+        &String::from_str(&env, "Parvo booster"),
+        &String::from_str(&env, "ipfs://hash2"),
+        &only_dog1,
+    );
+
+    // --- Assert ---
+    let treatments = client.get_dog_treatments(&dog1_id, &0, &10);
+    assert_eq!(treatments.len(), 1);
+    assert_eq!(treatments.get(0).unwrap().cost, 500);
+
+    let expenses = client.get_dog_expenses(&dog1_id, &0, &10);
+    assert_eq!(expenses.len(), 2);
+    assert_eq!(client.get_dog_expenses(&dog2_id, &0, &10).len(), 1);
+
+    let dossier = client.get_dog_dossier(&dog1_id);
+    assert_eq!(dossier.profile.name, String::from_str(&env, "Buddy"));
+    assert_eq!(dossier.total_treatments, 1);
+    assert_eq!(dossier.total_expenses, 2);
+    assert_eq!(dossier.treatments.len(), 1);
+    assert_eq!(dossier.expenses.len(), 2);
+    assert_eq!(dossier.spend.treatment_cost, 500);
+    assert_eq!(
+        dossier.spend.expense_share.get(token_id.clone()),
+        Some(500 + 300)
+    );
+
+    let dossier = client.get_dog_dossier(&dog2_id);
+    assert_eq!(dossier.spend.treatment_cost, 80);
+    assert_eq!(dossier.spend.expense_share.get(token_id.clone()), Some(500));
+
+    assert_eq!(
+        client.try_get_dog_dossier(&99),
+        Err(Ok(PawChainError::DogNotFound))
+    );
+}
+
+#[test]
+fn test_expense_rejects_unknown_or_duplicate_dogs() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, _token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Feeder"),
+        &String::from_str(&env, "Individual"),
+        &String::from_str(&env, "City"),
+        &String::from_str(&env, "N/A"),
+        &String::from_str(&env, "feeder@mail.com"),
+    );
+    let dog_id = client.register_dog(
+        &feeder_addr,
+        &String::from_str(&env, "Buddy"),
+        &3,
+        &String::from_str(&env, "Lab"),
+        &String::from_str(&env, "Shelter"),
+        &String::from_str(&env, "Healthy"),
+        &String::from_str(&env, "None"),
+    );
+
+    // --- Act & Assert ---
+    let mut unknown = Vec::new(&env);
+    unknown.push_back(42u64);
+    let result = client.try_record_expense(
+        &feeder_addr,
+        &token_id,
+        &100,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://receipt"),
+        &unknown,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::DogNotFound)));
+
+    let mut duplicate = Vec::new(&env);
+    duplicate.push_back(dog_id);
+    duplicate.push_back(dog_id);
+    let result = client.try_record_expense(
+        &feeder_addr,
+        &token_id,
+        &100,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://receipt"),
+        &duplicate,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::DuplicateDog)));
+}
+
+#[test]
+fn test_donor_history_and_summary() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let feeder1_addr = Address::generate(&env);
+    let feeder2_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    let other_donor = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    token_client.mint(&other_donor, &10000);
+    let feeder1_id = client.register_feeder(
+        &feeder1_addr,
+        &String::from_str(&env, "Feeder One"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "NY"),
+        &String::from_str(&env, "1"),
+        &String::from_str(&env, "1"),
+    );
+    let feeder2_id = client.register_feeder(
+        &feeder2_addr,
+        &String::from_str(&env, "Feeder Two"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "LA"),
+        &String::from_str(&env, "2"),
+        &String::from_str(&env, "2"),
+    );
+    let dog_id = client.register_dog(
+        &feeder1_addr,
+        &String::from_str(&env, "Buddy"),
+        &2,
+        &String::from_str(&env, "Indie"),
+        &String::from_str(&env, "Street"),
+        &String::from_str(&env, "Healthy"),
+        &String::from_str(&env, "None"),
+    );
+
+    // --- Act ---
+    client.donate(
+        &donor_addr,
+        &feeder1_id,
+        &token_id,
+        &100,
+        &String::from_str(&env, "Specific Dog"),
+        &Some(dog_id),
+    );
+    client.donate(
+        &other_donor,
+        &feeder1_id,
+        &token_id,
+        &999,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    client.donate(
+        &donor_addr,
+        &feeder1_id,
+        &token_id,
+        &200,
+        &String::from_str(&env, "Specific Dog"),
+        &Some(dog_id),
+    );
+    env.ledger().set_timestamp(123460000);
+    client.donate(
+        &donor_addr,
+        &feeder2_id,
+        &token_id,
+        &300,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+
+    // --- Assert ---
+    let history = client.get_donor_donations(&donor_addr, &0, &10);
+    assert_eq!(history.len(), 3);
+    for donation in history.iter() {
+        assert_eq!(donation.donor_address, donor_addr);
+    }
+    assert_eq!(client.get_donor_donations(&donor_addr, &2, &10).len(), 1);
+
+    let summary = client.get_donor_summary(&donor_addr);
+    assert_eq!(summary.total_given.get(token_id.clone()), Some(600));
+    assert_eq!(summary.donation_count, 3);
+    assert_eq!(summary.feeders_supported, 2);
+    assert_eq!(summary.dogs_supported, 1);
+    assert_eq!(summary.last_donation, 123460000);
+
+    // Unknown donors have an empty history
+    let stranger = Address::generate(&env);
+    assert_eq!(client.get_donor_donations(&stranger, &0, &10).len(), 0);
+    assert_eq!(client.get_donor_summary(&stranger).donation_count, 0);
+
+    // Donations must reference an existing dog
+    let result = client.try_donate(
+        &donor_addr,
+        &feeder1_id,
+        &token_id,
+        &100,
+        &String::from_str(&env, "Specific Dog"),
+        &Some(42),
+    );
+    assert_eq!(result, Err(Ok(PawChainError::DogNotFound)));
+}
+
+#[test]
+fn test_donations_in_allowlisted_tokens() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let usdc_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &1000);
+    usdc_client.mint(&donor_addr, &1000);
+    assert_eq!(
+        client.get_allowed_tokens(),
+        Vec::from_array(&env, [token_id.clone()])
+    );
+
+    // Tokens outside the allowlist are rejected
+    let result = client.try_donate(
+        &donor_addr,
+        &feeder_id,
+        &usdc_id,
+        &100,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::TokenNotAllowed)));
+
+    // --- Act ---
+    client.allow_token(&admin, &usdc_id);
+    client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &300,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    let usdc_donation = client.donate(
+        &donor_addr,
+        &feeder_id,
+        &usdc_id,
+        &200,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    client.record_expense(
+        &feeder_addr,
+        &usdc_id,
+        &50,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://hash"),
+        &Vec::new(&env),
+    );
+
+    // --- Assert ---
+    assert_eq!(client.get_donation(&usdc_donation).token, usdc_id);
+    assert_eq!(
+        token::Client::new(&env, &usdc_id).balance(&feeder_addr),
+        200
+    );
+    let feeder = client.get_feeder(&feeder_id);
+    assert_eq!(feeder.total_received.get(token_id.clone()), Some(300));
+    assert_eq!(feeder.total_received.get(usdc_id.clone()), Some(200));
+    assert_eq!(feeder.total_spent.get(usdc_id.clone()), Some(50));
+    assert_eq!(feeder.total_spent.get(token_id.clone()), None);
+    let summary = client.get_donor_summary(&donor_addr);
+    assert_eq!(summary.total_given.get(usdc_id.clone()), Some(200));
+    assert_eq!(summary.total_given.get(token_id.clone()), Some(300));
+
+    // Removing a token stops new donations and expenses in it
+    client.disallow_token(&admin, &usdc_id);
+    let result = client.try_record_expense(
+        &feeder_addr,
+        &usdc_id,
+        &50,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://hash"),
+        &Vec::new(&env),
+    );
+    assert_eq!(result, Err(Ok(PawChainError::TokenNotAllowed)));
+    assert_eq!(
+        client.try_disallow_token(&admin, &usdc_id),
+        Err(Ok(PawChainError::TokenNotAllowed))
+    );
+    assert_eq!(
+        client.try_allow_token(&donor_addr, &usdc_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_split_donation_across_feeders() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder1_addr, feeder1_id) = register_test_feeder(&env, &client);
+    let (feeder2_addr, feeder2_id) = register_test_feeder(&env, &client);
+    let (feeder3_addr, feeder3_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder2_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &1001);
+    let recipients = Vec::from_array(
+        &env,
+        [
+            (feeder1_id, None, 5000u32),
+            (feeder2_id, Some(dog_id), 3333u32),
+            (feeder3_id, None, 1667u32),
+        ],
+    );
+
+    // --- Act ---
+    let batch_id = client.donate_split(
+        &donor_addr,
+        &recipients,
+        &token_id,
+        &1001,
+        &String::from_str(&env, "Neighbourhood"),
+    );
+
+    // --- Assert ---
+    assert_eq!(batch_id, 1);
+    assert_eq!(token.balance(&feeder1_addr), 500);
+    assert_eq!(token.balance(&feeder2_addr), 333);
+    // Rounding dust goes to the last recipient
+    assert_eq!(token.balance(&feeder3_addr), 168);
+    assert_eq!(token.balance(&donor_addr), 0);
+
+    let batch = client.get_batch_donations(&batch_id);
+    assert_eq!(batch.len(), 3);
+    for donation in batch.iter() {
+        assert_eq!(donation.batch_id, Some(batch_id));
+    }
+    assert_eq!(batch.get(1).unwrap().dog_id, Some(dog_id));
+    assert_eq!(client.get_donor_summary(&donor_addr).feeders_supported, 3);
+    assert_eq!(client.get_total_donations(), 3);
+}
+
+#[test]
+fn test_split_donation_to_one_feeder_twice() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &1000);
+    let recipients = Vec::from_array(
+        &env,
+        [
+            (feeder_id, Some(dog_id), 5000u32),
+            (feeder_id, None, 5000u32),
+        ],
+    );
+
+    // --- Act ---
+    client.donate_split(
+        &donor_addr,
+        &recipients,
+        &token_id,
+        &1000,
+        &String::from_str(&env, "Neighbourhood"),
+    );
+
+    // --- Assert ---
+    // Both shares count towards what the feeder received
+    assert_eq!(token.balance(&feeder_addr), 1000);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(1000)
+    );
+    assert_eq!(
+        client.get_feeder_balance(&feeder_id, &token_id).available,
+        1000
+    );
+    assert_eq!(client.get_donor_summary(&donor_addr).feeders_supported, 1);
+}
+
+#[test]
+fn test_split_donation_validation() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (_, feeder1_id) = register_test_feeder(&env, &client);
+    let (_, feeder2_id) = register_test_feeder(&env, &client);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &1000);
+    let split = |recipients: Vec<(u64, Option<u64>, u32)>, amount: i128| {
+        client.try_donate_split(
+            &donor_addr,
+            &recipients,
+            &token_id,
+            &amount,
+            &String::from_str(&env, "General"),
+        )
+    };
+
+    // --- Act & Assert ---
+    // Shares must add up to the whole amount
+    let result = split(
+        Vec::from_array(&env, [(feeder1_id, None, 5000), (feeder2_id, None, 4000)]),
+        100,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::InvalidSplit)));
+    assert_eq!(
+        split(Vec::new(&env), 100),
+        Err(Ok(PawChainError::InvalidSplit))
+    );
+    let result = split(
+        Vec::from_array(&env, [(feeder1_id, None, 10000), (feeder2_id, None, 0)]),
+        100,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::InvalidSplit)));
+
+    // Every recipient must exist and receive something
+    let result = split(
+        Vec::from_array(&env, [(feeder1_id, None, 5000), (99, None, 5000)]),
+        100,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::FeederNotFound)));
+    let result = split(
+        Vec::from_array(
+            &env,
+            [(feeder1_id, Some(42), 5000), (feeder2_id, None, 5000)],
+        ),
+        100,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::DogNotFound)));
+    let result = split(
+        Vec::from_array(&env, [(feeder1_id, None, 9999), (feeder2_id, None, 1)]),
+        1,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::InvalidAmount)));
+
+    // Overflowing inputs are rejected rather than trapping
+    let result = split(
+        Vec::from_array(
+            &env,
+            [(feeder1_id, None, u32::MAX), (feeder2_id, None, 10_001)],
+        ),
+        100,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::InvalidSplit)));
+    let result = split(
+        Vec::from_array(&env, [(feeder1_id, None, 5000), (feeder2_id, None, 5000)]),
+        i128::MAX,
+    );
+    assert_eq!(result, Err(Ok(PawChainError::InvalidAmount)));
+
+    // Nothing moved on failure
+    assert_eq!(token.balance(&donor_addr), 1000);
+    assert_eq!(client.get_total_donations(), 0);
+}
+
+#[test]
+fn test_escrowed_donation_released_on_verified_expense() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let feeder_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+
+    // --- Act (escrow) ---
+    let donation_id = client.donate_escrowed(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &1000,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+
+    // --- Assert (escrow) ---
+    assert!(client.get_donation(&donation_id).escrowed);
+    assert_eq!(token.balance(&donor_addr), 9000);
+    assert_eq!(token.balance(&client.address), 1000);
+    assert_eq!(token.balance(&feeder_addr), 0);
+    assert_eq!(client.get_escrow_balance(&feeder_id, &token_id), 1000);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(1000)
+    );
+
+    // --- Act (expense recorded but not yet verified) ---
+    let expense1_id = client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &600,
+        &FundingSource::PawChain,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://receipt1"),
+        &Vec::new(&env),
+    );
+    assert_eq!(token.balance(&feeder_addr), 0);
+
+    // --- Act (verification releases escrow) ---
+    assert_eq!(client.verify_expense(&admin, &expense1_id), 600);
+    assert_eq!(
+        client.get_expense(&expense1_id).status,
+        ExpenseStatus::Verified
+    );
+    assert_eq!(token.balance(&feeder_addr), 600);
+    assert_eq!(client.get_escrow_balance(&feeder_id, &token_id), 400);
+
+    // Spend the feeder paid for themselves releases nothing
+    let self_funded_id = client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &700,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Treatment,
+        &String::from_str(&env, "Vet bill"),
+        &String::from_str(&env, "ipfs://receipt2"),
+        &Vec::new(&env),
+    );
+    assert_eq!(client.verify_expense(&admin, &self_funded_id), 0);
+    assert_eq!(token.balance(&feeder_addr), 600);
+    assert_eq!(client.get_escrow_balance(&feeder_id, &token_id), 400);
+
+    // The next PawChain-funded expense releases what is left in escrow
+    let expense2_id = client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &400,
+        &FundingSource::PawChain,
+        &ExpenseCategory::Treatment,
+        &String::from_str(&env, "Vet bill"),
+        &String::from_str(&env, "ipfs://receipt3"),
+        &Vec::new(&env),
+    );
+    assert_eq!(client.verify_expense(&admin, &expense2_id), 400);
+    assert_eq!(token.balance(&feeder_addr), 1000);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.get_escrow_balance(&feeder_id, &token_id), 0);
+
+    // --- Assert (guards) ---
+    assert_eq!(
+        client.try_verify_expense(&admin, &expense1_id),
+        Err(Ok(PawChainError::ExpenseAlreadyVerified))
+    );
+    assert_eq!(
+        client.try_verify_expense(&feeder_addr, &expense2_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_verify_expense(&admin, &99),
+        Err(Ok(PawChainError::ExpenseNotFound))
+    );
+
+    // Direct donations stay outside escrow
+    let direct_id = client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &100,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    assert!(!client.get_donation(&direct_id).escrowed);
+    assert_eq!(client.get_escrow_balance(&feeder_id, &token_id), 0);
+}
+
+#[test]
+fn test_migrate_upgrades_legacy_donations() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(&env, "Happy Paws NGO"),
+        &String::from_str(&env, "NGO"),
+        &String::from_str(&env, "New York"),
+        &String::from_str(&env, "NGO-12345"),
+        &String::from_str(&env, "contact@happypaws.org"),
+    );
+    for _ in 0..3 {
+        client.donate(
+            &donor_addr,
+            &feeder_id,
+            &token_id,
+            &100,
+            &String::from_str(&env, "General"),
+            &None,
+        );
+    }
+
+    // Rewind the stored donations to the v1 layout
+    env.as_contract(&client.address, || {
+        for id in 1..=3u64 {
+            let key = DataKey::Donation(id);
+            let mut record: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+            record.remove(Symbol::new(&env, "escrowed"));
+            env.storage().persistent().set(&key, &record);
+        }
+        env.storage().instance().set(&DataKey::SchemaVersion, &1u32);
+    });
+    assert!(client.try_get_donation(&1).is_err());
+
+    // --- Act ---
+    assert_eq!(client.migrate(&admin, &2), 1);
+    assert_eq!(client.get_schema_version(), 1);
+    assert_eq!(client.migrate(&admin, &2), 2);
+    while client.get_schema_version() < SCHEMA_VERSION {
+        client.migrate(&admin, &2);
+    }
+
+    // --- Assert ---
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+    for id in 1..=3u64 {
+        let donation = client.get_donation(&id);
+        assert_eq!(donation.amount, 100);
+        assert!(!donation.escrowed);
+    }
+    assert_eq!(
+        client.try_migrate(&admin, &2),
+        Err(Ok(PawChainError::SchemaUpToDate))
+    );
+}
+
+#[test]
+fn test_migrate_converts_single_token_totals() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &1000);
+    client.donate_escrowed(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &400,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &100,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://hash"),
+        &Vec::from_array(&env, [dog_id]),
+    );
+
+    // Rewind storage to the v2 single-token layout
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        let strip = |key: &DataKey, field: &str| {
+            let mut record: Map<Symbol, Val> = storage.get(key).unwrap();
+            record.remove(Symbol::new(&env, field));
+            storage.set(key, &record);
+        };
+        let flatten = |key: &DataKey, field: &str, amount: i128| {
+            let mut record: Map<Symbol, Val> = storage.get(key).unwrap();
+            record.set(Symbol::new(&env, field), amount.into_val(&env));
+            storage.set(key, &record);
+        };
+        strip(&DataKey::Donation(1), "token");
+        strip(&DataKey::Expense(1), "token");
+        flatten(
+            &DataKey::DonorSummary(donor_addr.clone()),
+            "total_given",
+            400,
+        );
+        flatten(&DataKey::Feeder(feeder_id), "total_received", 400);
+        flatten(&DataKey::Feeder(feeder_id), "total_spent", 100);
+        flatten(&DataKey::DogSpend(dog_id), "expense_share", 100);
+        storage.set(&DataKey::EscrowBalance(feeder_id), &300i128);
+        env.storage().instance().set(&DataKey::SchemaVersion, &2u32);
+    });
+    assert!(client.try_get_feeder(&feeder_id).is_err());
+
+    // --- Act ---
+    while client.get_schema_version() < SCHEMA_VERSION {
+        client.migrate(&admin, &10);
+    }
+
+    // --- Assert ---
+    assert_eq!(client.get_donation(&1).token, token_id);
+    assert_eq!(client.get_expense(&1).token, token_id);
+    let feeder = client.get_feeder(&feeder_id);
+    assert_eq!(feeder.total_received.get(token_id.clone()), Some(400));
+    assert_eq!(feeder.total_spent.get(token_id.clone()), Some(100));
+    assert_eq!(
+        client
+            .get_donor_summary(&donor_addr)
+            .total_given
+            .get(token_id.clone()),
+        Some(400)
+    );
+    assert_eq!(
+        client
+            .get_dog_dossier(&dog_id)
+            .spend
+            .expense_share
+            .get(token_id.clone()),
+        Some(100)
+    );
+    assert_eq!(client.get_escrow_balance(&feeder_id, &token_id), 300);
+}
+
+// Register a feeder with placeholder details and return (address, feeder_id)
+fn register_test_feeder(env: &Env, client: &PawChainContractClient) -> (Address, u64) {
+    let feeder_addr = Address::generate(env);
+    let feeder_id = client.register_feeder(
+        &feeder_addr,
+        &String::from_str(env, "Feeder"),
+        &String::from_str(env, "Individual"),
+        &String::from_str(env, "City"),
+        &String::from_str(env, "N/A"),
+        &String::from_str(env, "feeder@mail.com"),
+    );
+    (feeder_addr, feeder_id)
+}
+
+// Register a healthy dog for `feeder_addr` and return its id
+fn register_test_dog(env: &Env, client: &PawChainContractClient, feeder_addr: &Address) -> u64 {
+    client.register_dog(
+        feeder_addr,
+        &String::from_str(env, "Buddy"),
+        &2,
+        &String::from_str(env, "Indie"),
+        &String::from_str(env, "Street"),
+        &String::from_str(env, "Healthy"),
+        &String::from_str(env, "None"),
+    )
+}
+
+#[test]
+fn test_successful_campaign_pays_out() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+    token_client.mint(&donor1, &1000);
+    token_client.mint(&donor2, &1000);
+    let deadline = 123456789 + 1000;
+
+    // --- Act ---
+    let campaign_id = client.create_campaign(
+        &feeder_addr,
+        &String::from_str(&env, "Surgery for Buddy"),
+        &Some(dog_id),
+        &1500,
+        &deadline,
+    );
+    assert_eq!(client.donate_to_campaign(&donor1, &campaign_id, &800), 800);
+    assert_eq!(client.donate_to_campaign(&donor2, &campaign_id, &500), 500);
+    assert_eq!(client.donate_to_campaign(&donor1, &campaign_id, &200), 1000);
+
+    // --- Assert (funds held) ---
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.raised, 1500);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(token.balance(&client.address), 1500);
+    assert_eq!(
+        client.get_campaign_contribution(&campaign_id, &donor1),
+        1000
+    );
+    assert_eq!(
+        client.try_finalize_campaign(&campaign_id),
+        Err(Ok(PawChainError::CampaignStillActive))
+    );
+    assert_eq!(
+        client.try_record_campaign_payouts(&campaign_id, &0, &10),
+        Err(Ok(PawChainError::CampaignStillActive))
+    );
+
+    // --- Act (finalize after deadline) ---
+    env.ledger().set_timestamp(deadline);
+    assert_eq!(
+        client.try_donate_to_campaign(&donor2, &campaign_id, &100),
+        Err(Ok(PawChainError::CampaignClosed))
+    );
+    let campaign = client.finalize_campaign(&campaign_id);
+
+    // --- Assert (payout) ---
+    assert_eq!(campaign.status, CampaignStatus::Successful);
+    assert_eq!(token.balance(&feeder_addr), 1500);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.get_feeder_donations(&feeder_id, &0, &10).len(), 0);
+
+    // Each contributor's total is recorded once as a donation earmarked for the dog,
+    // a page at a time
+    assert_eq!(client.record_campaign_payouts(&campaign_id, &0, &1), 1);
+    assert_eq!(client.record_campaign_payouts(&campaign_id, &0, &10), 1);
+    assert_eq!(client.record_campaign_payouts(&campaign_id, &0, &10), 0);
+    assert_eq!(client.get_campaign_contribution(&campaign_id, &donor1), 0);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(1500)
+    );
+    let donations = client.get_feeder_donations(&feeder_id, &0, &10);
+    assert_eq!(donations.len(), 2);
+    assert_eq!(donations.get(0).unwrap().donor_address, donor1);
+    assert_eq!(donations.get(0).unwrap().amount, 1000);
+    assert_eq!(donations.get(1).unwrap().amount, 500);
+    assert_eq!(donations.get(1).unwrap().dog_id, Some(dog_id));
+    assert_eq!(
+        client
+            .get_donor_summary(&donor1)
+            .total_given
+            .get(token_id.clone()),
+        Some(1000)
+    );
+    assert_eq!(
+        client.get_dog_fund(&dog_id).get(token_id.clone()),
+        Some(1500)
+    );
+    assert_eq!(
+        client.try_claim_refund(&donor1, &campaign_id),
+        Err(Ok(PawChainError::CampaignClosed))
+    );
+    assert_eq!(
+        client.try_finalize_campaign(&campaign_id),
+        Err(Ok(PawChainError::CampaignClosed))
+    );
+}
+
+#[test]
+fn test_failed_campaign_refunds_donors() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    token_client.mint(&donor1, &1000);
+    token_client.mint(&donor2, &1000);
+    let deadline = 123456789 + 1000;
+    let campaign_id = client.create_campaign(
+        &feeder_addr,
+        &String::from_str(&env, "Winter food drive"),
+        &None,
+        &5000,
+        &deadline,
+    );
+    client.donate_to_campaign(&donor1, &campaign_id, &300);
+    client.donate_to_campaign(&donor2, &campaign_id, &700);
+    assert_eq!(
+        client.try_claim_refund(&donor1, &campaign_id),
+        Err(Ok(PawChainError::CampaignStillActive))
+    );
+
+    // --- Act (refund settles the expired campaign) ---
+    env.ledger().set_timestamp(deadline + 1);
+    assert_eq!(client.claim_refund(&donor1, &campaign_id), 300);
+
+    // --- Assert ---
+    assert_eq!(
+        client.get_campaign(&campaign_id).status,
+        CampaignStatus::Failed
+    );
+    assert_eq!(token.balance(&donor1), 1000);
+    assert_eq!(
+        client.try_claim_refund(&donor1, &campaign_id),
+        Err(Ok(PawChainError::NothingToRefund))
+    );
+    assert_eq!(
+        client.try_claim_refund(&stranger, &campaign_id),
+        Err(Ok(PawChainError::NothingToRefund))
+    );
+    assert_eq!(client.claim_refund(&donor2, &campaign_id), 700);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(token.balance(&feeder_addr), 0);
+    assert_eq!(
+        client.try_record_campaign_payouts(&campaign_id, &0, &10),
+        Err(Ok(PawChainError::CampaignClosed))
+    );
+    assert!(client.get_feeder(&feeder_id).total_received.is_empty());
+}
+
+#[test]
+fn test_campaign_payout_is_recorded_in_pages() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let treasury = Address::generate(&env);
+    client.set_fee_config(&admin, &100, &treasury);
+    let deadline = 123456789 + 1000;
+    let campaign_id = client.create_campaign(
+        &feeder_addr,
+        &String::from_str(&env, "New kennels"),
+        &None,
+        &1000,
+        &deadline,
+    );
+    for i in 1..=30 {
+        let donor = Address::generate(&env);
+        token_client.mint(&donor, &(100 + i));
+        client.donate_to_campaign(&donor, &campaign_id, &(100 + i));
+    }
+    env.ledger().set_timestamp(deadline);
+
+    // --- Act ---
+    // Paying out touches a fixed number of entries however many contributed
+    client.finalize_campaign(&campaign_id);
+    assert!(env.cost_estimate().resources().write_entries <= 8);
+    for start in [0u64, 10, 20] {
+        assert_eq!(
+            client.record_campaign_payouts(&campaign_id, &start, &10),
+            10
+        );
+    }
+
+    // --- Assert ---
+    // The fee taken on the total is charged in full across the contributors
+    let raised = 30 * 100 + 465;
+    let fee = raised / 100;
+    let donations = client.get_feeder_donations(&feeder_id, &0, &50);
+    assert_eq!(donations.len(), 30);
+    let mut fees = 0;
+    for donation in donations.iter() {
+        fees += donation.fee;
+    }
+    assert_eq!(fees, fee);
+    assert_eq!(token.balance(&treasury), fee);
+    assert_eq!(token.balance(&feeder_addr), raised - fee);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(raised - fee)
+    );
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!((campaign.unrecorded, campaign.unrecorded_fee), (0, 0));
+}
+
+#[test]
+fn test_create_campaign_validation() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    let (feeder1_addr, _) = register_test_feeder(&env, &client);
+    let (feeder2_addr, _) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder1_addr);
+    let title = String::from_str(&env, "Appeal");
+    let deadline = 123456789 + 1000;
+
+    // --- Act & Assert ---
+    assert_eq!(
+        client.try_create_campaign(&feeder1_addr, &title, &None, &0, &deadline),
+        Err(Ok(PawChainError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_create_campaign(&feeder1_addr, &title, &None, &100, &123456789),
+        Err(Ok(PawChainError::InvalidDeadline))
+    );
+    assert_eq!(
+        client.try_create_campaign(&feeder2_addr, &title, &Some(dog_id), &100, &deadline),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_get_campaign(&1),
+        Err(Ok(PawChainError::CampaignNotFound))
+    );
+    client.create_campaign(&feeder1_addr, &title, &Some(dog_id), &100, &deadline);
+    assert_eq!(client.list_campaigns(&1, &10).len(), 1);
+}
+
+#[test]
+fn test_subscription_pulls_from_allowance() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor = Address::generate(&env);
+    token_client.mint(&donor, &1000);
+    let month = 30 * 24 * 60 * 60;
+
+    let subscription_id =
+        client.create_subscription(&donor, &feeder_id, &100, &month, &Some(dog_id));
+    token.approve(&donor, &client.address, &250, &1000);
+
+    // --- Act (first pull is due immediately) ---
+    let donation_id = client.process_subscription(&subscription_id);
+
+    // --- Assert ---
+    let donation = client.get_donation(&donation_id);
+    assert_eq!(donation.donor_address, donor);
+    assert_eq!(donation.amount, 100);
+    assert_eq!(donation.dog_id, Some(dog_id));
+    assert_eq!(donation.purpose, String::from_str(&env, "Subscription"));
+    assert_eq!(token.balance(&feeder_addr), 100);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(100)
+    );
+    let subscription = client.get_subscription(&subscription_id);
+    assert_eq!(subscription.payments_made, 1);
+    assert_eq!(subscription.next_payment_due, 123456789 + month);
+    assert_eq!(
+        client.try_process_subscription(&subscription_id),
+        Err(Ok(PawChainError::PaymentNotDue))
+    );
+
+    // --- Act (missed intervals are not back-charged) ---
+    env.ledger().set_timestamp(123456789 + 3 * month + 5);
+    client.process_subscription(&subscription_id);
+    let subscription = client.get_subscription(&subscription_id);
+    assert_eq!(subscription.payments_made, 2);
+    assert_eq!(subscription.next_payment_due, 123456789 + 4 * month + 5);
+    assert_eq!(token.balance(&donor), 800);
+
+    // --- Act (allowance exhausted) ---
+    env.ledger().set_timestamp(123456789 + 5 * month);
+    assert_eq!(
+        client.try_process_subscription(&subscription_id),
+        Err(Ok(PawChainError::InsufficientAllowance))
+    );
+
+    // --- Act (cancel) ---
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_cancel_subscription(&stranger, &subscription_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    client.cancel_subscription(&donor, &subscription_id);
+    token.approve(&donor, &client.address, &1000, &1000);
+    assert_eq!(
+        client.try_process_subscription(&subscription_id),
+        Err(Ok(PawChainError::SubscriptionInactive))
+    );
+    assert_eq!(client.get_donor_summary(&donor).donation_count, 2);
+}
+
+#[test]
+fn test_create_subscription_validation() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    let (_feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let donor = Address::generate(&env);
+
+    // --- Act & Assert ---
+    assert_eq!(
+        client.try_create_subscription(&donor, &feeder_id, &0, &60, &None),
+        Err(Ok(PawChainError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_create_subscription(&donor, &feeder_id, &100, &0, &None),
+        Err(Ok(PawChainError::InvalidInterval))
+    );
+    assert_eq!(
+        client.try_create_subscription(&donor, &99, &100, &60, &None),
+        Err(Ok(PawChainError::FeederNotFound))
+    );
+    assert_eq!(
+        client.try_get_subscription(&1),
+        Err(Ok(PawChainError::SubscriptionNotFound))
+    );
+    client.create_subscription(&donor, &feeder_id, &100, &60, &None);
+    assert_eq!(client.list_subscriptions(&1, &10).len(), 1);
+}
+
+#[test]
+fn test_dog_sponsorship_lifecycle() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor_addr = Address::generate(&env);
+    let other_donor = Address::generate(&env);
+
+    // --- Act ---
+    let sponsorship_id = client.sponsor_dog(&donor_addr, &dog_id, &250);
+    client.sponsor_dog(&other_donor, &dog_id, &100);
+
+    // --- Assert ---
+    let sponsorship = client.get_sponsorship(&sponsorship_id);
+    assert_eq!(sponsorship.donor, donor_addr);
+    assert_eq!(sponsorship.monthly_amount, 250);
+    assert!(sponsorship.is_active);
+    assert_eq!(client.get_dog_sponsors(&dog_id, &0, &10).len(), 2);
+    assert_eq!(client.get_donor_sponsorships(&donor_addr, &0, &10).len(), 1);
+    assert_eq!(
+        client.try_sponsor_dog(&donor_addr, &dog_id, &50),
+        Err(Ok(PawChainError::AlreadySponsoring))
+    );
+    assert_eq!(
+        client.try_sponsor_dog(&donor_addr, &dog_id, &0),
+        Err(Ok(PawChainError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_end_sponsorship(&other_donor, &sponsorship_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+
+    // Ending frees the donor to pledge again
+    client.end_sponsorship(&donor_addr, &sponsorship_id);
+    let ended = client.get_sponsorship(&sponsorship_id);
+    assert!(!ended.is_active);
+    assert_eq!(ended.ended_at, Some(env.ledger().timestamp()));
+    assert_eq!(
+        client.try_end_sponsorship(&donor_addr, &sponsorship_id),
+        Err(Ok(PawChainError::SponsorshipInactive))
+    );
+    client.sponsor_dog(&donor_addr, &dog_id, &300);
+    assert_eq!(client.get_donor_sponsorships(&donor_addr, &0, &10).len(), 2);
+}
+
+#[test]
+fn test_deactivating_dog_ends_sponsorships() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let (other_feeder, _) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let mut donors = Vec::new(&env);
+    for _ in 0..30 {
+        let donor = Address::generate(&env);
+        client.sponsor_dog(&donor, &dog_id, &100);
+        donors.push_back(donor);
+    }
+    let donor = donors.get_unchecked(0);
+    assert_eq!(
+        client.try_deactivate_dog(&other_feeder, &dog_id, &DogExitReason::Adopted),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+
+    // --- Act ---
+    env.ledger().set_timestamp(123456789 + 500);
+    client.deactivate_dog(&feeder_addr, &dog_id, &DogExitReason::Adopted);
+
+    // --- Assert ---
+    // Leaving touches a fixed number of entries however many sponsor the dog
+    assert!(env.cost_estimate().resources().write_entries <= 8);
+    let dog = client.get_dog(&dog_id);
+    assert!(!dog.is_active);
+    assert_eq!(dog.departed_at, Some(123456789 + 500));
+    assert_eq!(client.get_feeder_stats(&feeder_id).dogs_adopted, 1);
+    let sponsorships = client.get_dog_sponsors(&dog_id, &0, &50);
+    assert_eq!(sponsorships.len(), 30);
+    for sponsorship in sponsorships.iter() {
+        assert!(!sponsorship.is_active);
+        assert_eq!(sponsorship.ended_at, dog.departed_at);
+    }
+    let sponsorship = client
+        .get_donor_sponsorships(&donor, &0, &10)
+        .get_unchecked(0);
+    assert!(!sponsorship.is_active);
+    assert_eq!(
+        client.get_sponsorship(&sponsorship.sponsorship_id),
+        sponsorship
+    );
+    assert_eq!(
+        client.try_end_sponsorship(&donor, &sponsorship.sponsorship_id),
+        Err(Ok(PawChainError::SponsorshipInactive))
+    );
+    assert_eq!(
+        client.try_sponsor_dog(&donor, &dog_id, &100),
+        Err(Ok(PawChainError::DogInactive))
+    );
+    assert_eq!(
+        client.try_deactivate_dog(&feeder_addr, &dog_id, &DogExitReason::Deceased),
+        Err(Ok(PawChainError::DogInactive))
+    );
+}
+
+#[test]
+fn test_matching_pool_matches_donations() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let (other_addr, other_id) = register_test_feeder(&env, &client);
+    let sponsor = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&sponsor, &150);
+    token_client.mint(&donor_addr, &1000);
+    let pool_id = client.create_matching_pool(
+        &sponsor,
+        &token_id,
+        &150,
+        &10000,
+        &Vec::from_array(&env, [feeder_id]),
+        &(env.ledger().timestamp() + 86400),
+    );
+    assert_eq!(token.balance(&client.address), 150);
+    let donate = |feeder_id: u64, amount: i128| {
+        client.donate(
+            &donor_addr,
+            &feeder_id,
+            &token_id,
+            &amount,
+            &String::from_str(&env, "General"),
+            &None,
+        )
+    };
+
+    // --- Act ---
+    let first = donate(feeder_id, 100);
+    donate(feeder_id, 100);
+    donate(feeder_id, 100);
+    donate(other_id, 100);
+
+    // --- Assert ---
+    // Matched 1:1 until the cap ran out; ineligible feeders are not matched
+    assert_eq!(token.balance(&feeder_addr), 300 + 100 + 50);
+    assert_eq!(token.balance(&other_addr), 100);
+    assert_eq!(client.get_matching_pool(&pool_id).remaining, 0);
+    let matched = client.get_donation(&(first + 1));
+    assert_eq!(matched.donor_address, sponsor);
+    assert_eq!(matched.amount, 100);
+    assert_eq!(matched.match_of, Some(first));
+    assert_eq!(client.get_donation(&first).match_of, None);
+    assert_eq!(client.get_total_donations(), 6);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(450)
+    );
+}
+
+#[test]
+fn test_matching_pool_reclaim_after_expiry() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (_, feeder_id) = register_test_feeder(&env, &client);
+    let sponsor = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&sponsor, &1000);
+    token_client.mint(&donor_addr, &1000);
+    let expires_at = env.ledger().timestamp() + 86400;
+    let eligible = Vec::from_array(&env, [feeder_id]);
+    assert_eq!(
+        client.try_create_matching_pool(&sponsor, &token_id, &1000, &0, &eligible, &expires_at),
+        Err(Ok(PawChainError::InvalidMatchingPool))
+    );
+    assert_eq!(
+        client.try_create_matching_pool(
+            &sponsor,
+            &token_id,
+            &1000,
+            &5000,
+            &eligible,
+            &env.ledger().timestamp()
+        ),
+        Err(Ok(PawChainError::InvalidDeadline))
+    );
+    // Half match: 50 per 100 donated
+    let pool_id =
+        client.create_matching_pool(&sponsor, &token_id, &1000, &5000, &eligible, &expires_at);
+    client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &100,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    assert_eq!(
+        client.try_reclaim_matching_pool(&sponsor, &pool_id),
+        Err(Ok(PawChainError::MatchingPoolStillActive))
+    );
+
+    // --- Act ---
+    env.ledger().set_timestamp(expires_at);
+    client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &100,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    assert_eq!(
+        client.try_reclaim_matching_pool(&donor_addr, &pool_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    let reclaimed = client.reclaim_matching_pool(&sponsor, &pool_id);
+
+    // --- Assert ---
+    // Only the donation before expiry was matched
+    assert_eq!(reclaimed, 950);
+    assert_eq!(token.balance(&sponsor), 950);
+    assert_eq!(client.get_total_donations(), 3);
+    assert_eq!(
+        client.try_reclaim_matching_pool(&sponsor, &pool_id),
+        Err(Ok(PawChainError::NothingToRefund))
+    );
+}
+
+#[test]
+fn test_platform_fee_routed_to_treasury() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let treasury = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    assert_eq!(client.get_fee_config(), None);
+    assert_eq!(
+        client.try_set_fee_config(&admin, &501, &treasury),
+        Err(Ok(PawChainError::FeeTooHigh))
+    );
+    assert_eq!(
+        client.try_set_fee_config(&donor_addr, &100, &treasury),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+
+    // --- Act ---
+    client.set_fee_config(&admin, &250, &treasury);
+    let donation_id = client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &1000,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &2000,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+
+    // --- Assert ---
+    let donation = client.get_donation(&donation_id);
+    assert_eq!(donation.amount, 1000);
+    assert_eq!(donation.fee, 25);
+    assert_eq!(token.balance(&treasury), 25 + 50);
+    assert_eq!(token.balance(&feeder_addr), 975 + 1950);
+    assert_eq!(client.get_fee_totals().get(token_id.clone()), Some(75));
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(2925)
+    );
+    assert_eq!(
+        client
+            .get_donor_summary(&donor_addr)
+            .total_given
+            .get(token_id.clone()),
+        Some(3000)
+    );
+    assert_eq!(client.get_fee_config().unwrap().fee_bps, 250);
+}
+
+#[test]
+fn test_dog_fund_tracks_earmarked_donations() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let donate = |dog_id: Option<u64>, amount: i128| {
+        client.donate(
+            &donor_addr,
+            &feeder_id,
+            &token_id,
+            &amount,
+            &String::from_str(&env, "Specific Dog"),
+            &dog_id,
+        );
+    };
+
+    // --- Act ---
+    donate(Some(dog_id), 1000);
+    donate(None, 500);
+    client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &300,
+        &FundingSource::PawChain,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://hash"),
+        &Vec::from_array(&env, [dog_id]),
+    );
+    let treat = |treatment_type: &str, cost: i128, funding_source: FundingSource| {
+        client.record_treatment(
+            &feeder_addr,
+            &dog_id,
+            &String::from_str(&env, treatment_type),
+            &String::from_str(&env, "Routine care"),
+            &cost,
+            &funding_source,
+            &String::from_str(&env, "Dr. Vet"),
+            &None,
+            &false,
+            &String::from_str(&env, "Successful"),
+        );
+    };
+    treat("Vaccination", 200, FundingSource::PawChain);
+    treat("Checkup", 50, FundingSource::SelfFunded);
+
+    // --- Assert ---
+    // Untagged donations are not earmarked; PawChain-funded expenses and
+    // treatments draw the earmark down, while self-funded care leaves it alone
+    assert_eq!(
+        client.get_dog_fund(&dog_id).get(token_id.clone()),
+        Some(1000 - 300 - 200)
+    );
+    let balance = client.get_feeder_balance(&feeder_id, &token_id);
+    assert_eq!(balance.pawchain_spent, 300 + 200);
+    assert_eq!(balance.external_spent, 50);
+
+    // Spending beyond the earmark stops at zero
+    treat("Surgery", 900, FundingSource::PawChain);
+    assert_eq!(client.get_dog_fund(&dog_id).get(token_id.clone()), Some(0));
+    assert_eq!(
+        client.try_get_dog_fund(&99),
+        Err(Ok(PawChainError::DogNotFound))
+    );
+}
+
+#[test]
+fn test_dog_must_be_in_feeders_care() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let (feeder1_addr, feeder1_id) = register_test_feeder(&env, &client);
+    let (feeder2_addr, _) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder2_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let purpose = String::from_str(&env, "Specific Dog");
+    let other_dog = Some(dog_id);
+
+    // --- Act & Assert ---
+    // Gifts to one feeder cannot be earmarked for another feeder's dog
+    assert_eq!(
+        client.try_donate(
+            &donor_addr,
+            &feeder1_id,
+            &token_id,
+            &1000,
+            &purpose,
+            &other_dog
+        ),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_donate_escrowed(
+            &donor_addr,
+            &feeder1_id,
+            &token_id,
+            &1000,
+            &purpose,
+            &other_dog
+        ),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_donate_split(
+            &donor_addr,
+            &Vec::from_array(&env, [(feeder1_id, other_dog, 10_000)]),
+            &token_id,
+            &1000,
+            &purpose,
+        ),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_create_subscription(&donor_addr, &feeder1_id, &500, &1000, &other_dog),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+
+    // Nor can a feeder charge spending or treatments to another feeder's dog
+    assert_eq!(
+        client.try_record_expense(
+            &feeder1_addr,
+            &token_id,
+            &100,
+            &FundingSource::SelfFunded,
+            &ExpenseCategory::Food,
+            &String::from_str(&env, "Kibble"),
+            &String::from_str(&env, "ipfs://hash"),
+            &Vec::from_array(&env, [dog_id]),
+        ),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_record_treatment(
+            &feeder1_addr,
+            &dog_id,
+            &String::from_str(&env, "Vaccination"),
+            &String::from_str(&env, "Rabies shot"),
+            &200,
+            &FundingSource::SelfFunded,
+            &String::from_str(&env, "Dr. Vet"),
+            &None,
+            &false,
+            &String::from_str(&env, "Successful"),
+        ),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(token_client.balance(&donor_addr), 10000);
+    assert!(client.get_dog_fund(&dog_id).is_empty());
+    assert_eq!(client.get_dog_treatments(&dog_id, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_departed_dog_fund_rolls_into_general_fund() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let donate = |amount: i128| {
+        client.donate(
+            &donor_addr,
+            &feeder_id,
+            &token_id,
+            &amount,
+            &String::from_str(&env, "Specific Dog"),
+            &Some(dog_id),
+        );
+    };
+    donate(600);
+
+    // --- Act ---
+    client.deactivate_dog(&feeder_addr, &dog_id, &DogExitReason::Deceased);
+    donate(100);
+
+    // --- Assert ---
+    assert!(client.get_dog_fund(&dog_id).is_empty());
+    assert_eq!(
+        client.get_general_fund(&feeder_id).get(token_id.clone()),
+        Some(700)
+    );
+}
+
+#[test]
+fn test_emergency_appeal_closes_on_target() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    let deadline = env.ledger().timestamp() + 86400;
+    assert_eq!(
+        client.try_open_emergency(&feeder_addr, &dog_id, &token_id, &500, &deadline),
+        Err(Ok(PawChainError::DogNotCritical))
+    );
+    client.update_dog_health(
+        &feeder_addr,
+        &dog_id,
+        &String::from_str(&env, "Critical"),
+        &String::from_str(&env, "Hit by a car"),
+    );
+    let donate = |purpose: &str, amount: i128| {
+        client.donate(
+            &donor_addr,
+            &feeder_id,
+            &token_id,
+            &amount,
+            &String::from_str(&env, purpose),
+            &Some(dog_id),
+        );
+    };
+
+    // --- Act ---
+    let appeal_id = client.open_emergency(&feeder_addr, &dog_id, &token_id, &500, &deadline);
+    assert_eq!(
+        client.try_open_emergency(&feeder_addr, &dog_id, &token_id, &500, &deadline),
+        Err(Ok(PawChainError::EmergencyAlreadyOpen))
+    );
+    donate("Emergency", 300);
+    donate("General", 300);
+
+    // --- Assert ---
+    let active = client.list_active_emergencies(&0, &10);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get(0).unwrap().raised, 300);
+
+    donate("Emergency", 250);
+    let appeal = client.get_emergency(&appeal_id);
+    assert_eq!(appeal.status, EmergencyStatus::TargetReached);
+    assert_eq!(appeal.raised, 550);
+    assert_eq!(client.get_dog_emergency(&dog_id), None);
+    assert_eq!(client.list_active_emergencies(&0, &10).len(), 0);
+}
+
+#[test]
+fn test_emergency_appeal_closes_on_health_change_or_expiry() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let dog1_id = register_test_dog(&env, &client, &feeder_addr);
+    let dog2_id = register_test_dog(&env, &client, &feeder_addr);
+    for dog_id in [dog1_id, dog2_id] {
+        client.update_dog_health(
+            &feeder_addr,
+            &dog_id,
+            &String::from_str(&env, "Critical"),
+            &String::from_str(&env, "Parvovirus"),
+        );
+    }
+    let now = env.ledger().timestamp();
+    let appeal1 = client.open_emergency(&feeder_addr, &dog1_id, &token_id, &500, &(now + 100));
+    client.open_emergency(&feeder_addr, &dog2_id, &token_id, &500, &(now + 100));
+    assert_eq!(client.list_active_emergencies(&0, &10).len(), 2);
+    assert_eq!(client.list_active_emergencies(&1, &10).len(), 1);
+
+    // --- Act ---
+    client.update_dog_health(
+        &feeder_addr,
+        &dog1_id,
+        &String::from_str(&env, "Recovering"),
+        &String::from_str(&env, "Parvovirus"),
+    );
+
+    // --- Assert ---
+    assert_eq!(
+        client.get_emergency(&appeal1).status,
+        EmergencyStatus::HealthChanged
+    );
+    assert_eq!(client.list_active_emergencies(&0, &10).len(), 1);
+
+    // Past the deadline the appeal drops off and a new one can be opened
+    env.ledger().set_timestamp(now + 100);
+    assert_eq!(client.list_active_emergencies(&0, &10).len(), 0);
+    let appeal3 = client.open_emergency(&feeder_addr, &dog2_id, &token_id, &500, &(now + 200));
+    assert_eq!(client.get_emergency(&2).status, EmergencyStatus::Expired);
+    assert_eq!(
+        client.get_dog_emergency(&dog2_id).unwrap().appeal_id,
+        appeal3
+    );
+}
+
+#[test]
+fn test_expired_appeals_leave_the_open_list() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let mut dogs = Vec::new(&env);
+    for _ in 0..4 {
+        let dog_id = register_test_dog(&env, &client, &feeder_addr);
+        client.update_dog_health(
+            &feeder_addr,
+            &dog_id,
+            &String::from_str(&env, "Critical"),
+            &String::from_str(&env, "Parvovirus"),
+        );
+        dogs.push_back(dog_id);
+    }
+    let now = env.ledger().timestamp();
+    for i in 0..3 {
+        client.open_emergency(
+            &feeder_addr,
+            &dogs.get_unchecked(i),
+            &token_id,
+            &500,
+            &(now + 100),
+        );
+    }
+
+    // --- Act ---
+    // The first three run out without any further activity
+    env.ledger().set_timestamp(now + 100);
+    let appeal4 = client.open_emergency(
+        &feeder_addr,
+        &dogs.get_unchecked(3),
+        &token_id,
+        &500,
+        &(now + 200),
+    );
+
+    // --- Assert ---
+    // Opening the next appeal moves the head past the expired ones
+    let active = client.list_active_emergencies(&0, &10);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get_unchecked(0).appeal_id, appeal4);
+    env.as_contract(&client.address, || {
+        let head: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::EmergencyHead)
+            .unwrap();
+        assert_eq!(head, 3);
+    });
+}
+
+#[test]
+fn test_rejected_expense_is_reversed() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog1_id = register_test_dog(&env, &client, &feeder_addr);
+    let dog2_id = register_test_dog(&env, &client, &feeder_addr);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &300);
+    client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &300,
+        &String::from_str(&env, "Specific Dog"),
+        &Some(dog1_id),
+    );
+    let record_funded = |amount: i128, dogs: Vec<u64>, funding_source: FundingSource| {
+        client.record_expense(
+            &feeder_addr,
+            &token_id,
+            &amount,
+            &funding_source,
+            &ExpenseCategory::Food,
+            &String::from_str(&env, "Kibble"),
+            &String::from_str(&env, "ipfs://hash"),
+            &dogs,
+        )
+    };
+    let record =
+        |amount: i128, dogs: Vec<u64>| record_funded(amount, dogs, FundingSource::SelfFunded);
+    let expense1_id = record(400, Vec::from_array(&env, [dog1_id, dog2_id]));
+    let expense2_id = record(100, Vec::from_array(&env, [dog1_id]));
+    let expense3_id = record(50, Vec::new(&env));
+    assert_eq!(client.list_pending_expenses(&0, &10).len(), 3);
+
+    // A PawChain-funded expense draws on dog 1's earmark
+    let funded_id = record_funded(
+        200,
+        Vec::from_array(&env, [dog1_id]),
+        FundingSource::PawChain,
+    );
+    assert_eq!(
+        client.get_dog_fund(&dog1_id).get(token_id.clone()),
+        Some(100)
+    );
+    assert_eq!(
+        client.get_expense(&funded_id).fund_draws,
+        Map::from_array(&env, [(dog1_id, 200)])
+    );
+
+    // --- Act ---
+    client.verify_expense(&admin, &expense2_id);
+    client.reject_expense(
+        &admin,
+        &expense1_id,
+        &String::from_str(&env, "Receipt unreadable"),
+    );
+    client.reject_expense(&admin, &funded_id, &String::from_str(&env, "Duplicate"));
+
+    // --- Assert ---
+    let rejected = client.get_expense(&expense1_id);
+    assert_eq!(rejected.status, ExpenseStatus::Rejected);
+    assert_eq!(
+        rejected.rejection_reason,
+        Some(String::from_str(&env, "Receipt unreadable"))
+    );
+    let pending = client.list_pending_expenses(&0, &10);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap().expense_id, expense3_id);
+
+    // Totals and stats only count the expenses still standing
+    let feeder = client.get_feeder(&feeder_id);
+    assert_eq!(feeder.total_spent.get(token_id.clone()), Some(150));
+    assert_eq!(client.get_feeder_stats(&feeder_id).dogs_fed, 1);
+    let spend = client.get_dog_dossier(&dog2_id).spend;
+    assert_eq!(spend.expense_share.get(token_id.clone()), Some(0));
+
+    // The rejected draw goes back into dog 1's earmark
+    assert_eq!(
+        client.get_dog_fund(&dog1_id).get(token_id.clone()),
+        Some(300)
+    );
+
+    assert_eq!(
+        client.try_reject_expense(&admin, &expense2_id, &String::from_str(&env, "Late")),
+        Err(Ok(PawChainError::ExpenseNotPending))
+    );
+    assert_eq!(
+        client.try_verify_expense(&admin, &expense1_id),
+        Err(Ok(PawChainError::ExpenseNotPending))
+    );
+    assert_eq!(
+        client.try_reject_expense(&feeder_addr, &expense3_id, &String::from_str(&env, "No")),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_migrate_converts_expense_verified_flag() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    for _ in 0..2 {
+        client.record_expense(
+            &feeder_addr,
+            &token_id,
+            &100,
+            &FundingSource::SelfFunded,
+            &ExpenseCategory::Food,
+            &String::from_str(&env, "Kibble"),
+            &String::from_str(&env, "ipfs://hash"),
+            &Vec::new(&env),
+        );
+    }
+
+    // Rewind storage to the v9 layout: expense 1 verified, expense 2 not, no queue
+    env.as_contract(&client.address, || {
+        for (id, verified) in [(1u64, true), (2u64, false)] {
+            let key = DataKey::Expense(id);
+            let mut record: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+            record.remove(Symbol::new(&env, "status"));
+            record.remove(Symbol::new(&env, "rejection_reason"));
+            record.set(Symbol::new(&env, "verified"), verified.into_val(&env));
+            env.storage().persistent().set(&key, &record);
+        }
+        let persistent = env.storage().persistent();
+        persistent.remove(&DataKey::IndexLen(IndexKey::PendingExpenses));
+        persistent.remove(&DataKey::IndexBucket(IndexKey::PendingExpenses, 0));
+        env.storage().instance().set(&DataKey::SchemaVersion, &9u32);
+    });
+
+    // --- Act ---
+    client.migrate(&admin, &10);
+
+    // --- Assert ---
+    assert_eq!(client.get_schema_version(), 10);
+    assert_eq!(client.get_expense(&1).status, ExpenseStatus::Verified);
+    assert_eq!(client.get_expense(&2).status, ExpenseStatus::Pending);
+    let pending = client.list_pending_expenses(&0, &10);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap().expense_id, 2);
+}
+
+#[test]
+fn test_auditors_attest_expenses_and_treatments() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let vet = Address::generate(&env);
+    let accountant = Address::generate(&env);
+    client.add_auditor(&admin, &vet, &String::from_str(&env, "Dr. Vet"));
+    client.add_auditor(&admin, &accountant, &String::from_str(&env, "Books & Co"));
+    let expense_id = client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &100,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://hash"),
+        &Vec::new(&env),
+    );
+    let treatment_id = client.record_treatment(
+        &feeder_addr,
+        &dog_id,
+        &String::from_str(&env, "Vaccination"),
+        &String::from_str(&env, "Rabies shot"),
+        &50,
+        &FundingSource::SelfFunded,
+        &String::from_str(&env, "Dr. Vet"),
+        &None,
+        &false,
+        &String::from_str(&env, "Successful"),
+    );
+    let note = String::from_str(&env, "ipfs://notes");
+
+    // --- Act ---
+    let attestation_id = client.attest_expense(&vet, &expense_id, &note);
+    client.attest_expense(&accountant, &expense_id, &note);
+    client.attest_treatment(&vet, &treatment_id, &note);
+
+    // --- Assert ---
+    let attestation = client.get_attestation(&attestation_id);
+    assert_eq!(attestation.auditor, vet);
+    assert_eq!(attestation.subject, AttestationSubject::Expense(expense_id));
+    assert_eq!(attestation.note_hash, note);
+    let expense_subject = AttestationSubject::Expense(expense_id);
+    assert_eq!(client.get_attestation_count(&expense_subject), 2);
+    assert_eq!(client.get_attestations(&expense_subject, &0, &10).len(), 2);
+    assert_eq!(
+        client.get_attestation_count(&AttestationSubject::Treatment(treatment_id)),
+        1
+    );
+    assert_eq!(client.get_auditor(&vet).attestation_count, 2);
+    let summary = client.get_feeder_audit_summary(&feeder_id);
+    assert_eq!(summary.attestations, 3);
+    assert_eq!(summary.distinct_auditors, 2);
+
+    assert_eq!(
+        client.try_attest_expense(&vet, &expense_id, &note),
+        Err(Ok(PawChainError::AlreadyAttested))
+    );
+    assert_eq!(
+        client.try_attest_expense(&feeder_addr, &expense_id, &note),
+        Err(Ok(PawChainError::AuditorNotFound))
+    );
+}
+
+#[test]
+fn test_auditor_registry_is_admin_managed() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let auditor = Address::generate(&env);
+    let name = String::from_str(&env, "Auditor");
+    assert_eq!(
+        client.try_add_auditor(&feeder_addr, &auditor, &name),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    let expense_id = client.record_expense(
+        &feeder_addr,
+        &token_id,
+        &100,
+        &FundingSource::SelfFunded,
+        &ExpenseCategory::Food,
+        &String::from_str(&env, "Kibble"),
+        &String::from_str(&env, "ipfs://hash"),
+        &Vec::new(&env),
+    );
+
+    // --- Act ---
+    client.add_auditor(&admin, &auditor, &name);
+    assert_eq!(
+        client.try_add_auditor(&admin, &auditor, &name),
+        Err(Ok(PawChainError::AuditorAlreadyRegistered))
+    );
+    client.remove_auditor(&admin, &auditor);
+
+    // --- Assert ---
+    assert!(!client.get_auditor(&auditor).is_active);
+    assert_eq!(
+        client.try_attest_expense(&auditor, &expense_id, &String::from_str(&env, "n")),
+        Err(Ok(PawChainError::AuditorNotFound))
+    );
+    client.add_auditor(&admin, &auditor, &name);
+    client.attest_expense(&auditor, &expense_id, &String::from_str(&env, "n"));
+    let auditors = client.list_auditors(&0, &10);
+    assert_eq!(auditors.len(), 1);
+    assert_eq!(auditors.get_unchecked(0).address, auditor);
+    assert!(auditors.get_unchecked(0).is_active);
+    assert_eq!(client.list_auditors(&1, &10).len(), 0);
+}
+
+#[test]
+fn test_feeder_balance_limits_pawchain_spend() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &1000);
+    client.donate(
+        &donor_addr,
+        &feeder_id,
+        &token_id,
+        &500,
+        &String::from_str(&env, "General"),
+        &None,
+    );
+    let record = |amount: i128, funding_source: FundingSource| {
+        client.try_record_expense(
+            &feeder_addr,
+            &token_id,
+            &amount,
+            &funding_source,
+            &ExpenseCategory::Food,
+            &String::from_str(&env, "Kibble"),
+            &String::from_str(&env, "ipfs://hash"),
+            &Vec::new(&env),
+        )
+    };
+
+    // --- Act ---
+    let funded = record(300, FundingSource::PawChain).unwrap().unwrap();
+    let overspend = record(300, FundingSource::PawChain);
+    record(300, FundingSource::SelfFunded).unwrap().unwrap();
+    record(100, FundingSource::External).unwrap().unwrap();
+
+    // --- Assert ---
+    assert_eq!(overspend, Err(Ok(PawChainError::InsufficientBalance)));
+    let balance = client.get_feeder_balance(&feeder_id, &token_id);
+    assert_eq!(balance.received, 500);
+    assert_eq!(balance.pawchain_spent, 300);
+    assert_eq!(balance.external_spent, 400);
+    assert_eq!(balance.available, 200);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_spent
+            .get(token_id.clone()),
+        Some(700)
+    );
+    assert_eq!(
+        client.get_expense(&funded).funding_source,
+        FundingSource::PawChain
+    );
+
+    // Rejecting a funded expense frees its balance again
+    client.reject_expense(&admin, &funded, &String::from_str(&env, "Duplicate"));
+    let balance = client.get_feeder_balance(&feeder_id, &token_id);
+    assert_eq!(balance.pawchain_spent, 0);
+    assert_eq!(balance.available, 500);
+}
+
+#[test]
+fn test_custom_expense_categories_map_to_counters() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let rescue = String::from_str(&env, "Rescue");
+    let transport = String::from_str(&env, "Transport");
+    let record = |category: ExpenseCategory| {
+        client.try_record_expense(
+            &feeder_addr,
+            &token_id,
+            &100,
+            &FundingSource::SelfFunded,
+            &category,
+            &String::from_str(&env, "Expense"),
+            &String::from_str(&env, "ipfs://hash"),
+            &Vec::from_array(&env, [dog_id]),
+        )
+    };
+
+    // --- Act ---
+    client.register_expense_category(&admin, &rescue, &Some(ActivityCounter::Rescued));
+    client.register_expense_category(&admin, &transport, &None);
+    record(ExpenseCategory::Custom(rescue.clone()))
+        .unwrap()
+        .unwrap();
+    record(ExpenseCategory::Custom(transport.clone()))
+        .unwrap()
+        .unwrap();
+    record(ExpenseCategory::Medicine).unwrap().unwrap();
+    let unknown = record(ExpenseCategory::Custom(String::from_str(&env, "Toys")));
+
+    // --- Assert ---
+    assert_eq!(unknown, Err(Ok(PawChainError::UnknownCategory)));
+    let stats = client.get_feeder_stats(&feeder_id);
+    // Registering the dog already counted one rescue
+    assert_eq!(stats.dogs_rescued, 2);
+    assert_eq!(stats.dogs_treated, 1);
+    assert_eq!(stats.dogs_fed, 0);
+    let categories = client.get_custom_categories();
+    assert_eq!(categories.len(), 2);
+    assert_eq!(categories.get(transport), Some(None));
+    assert_eq!(
+        client.try_register_expense_category(&admin, &rescue, &None),
+        Err(Ok(PawChainError::CategoryAlreadyRegistered))
+    );
+    assert_eq!(
+        client.try_register_expense_category(&feeder_addr, &String::from_str(&env, "Toys"), &None),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_migrate_converts_expense_category_strings() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    for _ in 0..2 {
+        client.record_expense(
+            &feeder_addr,
+            &token_id,
+            &100,
+            &FundingSource::SelfFunded,
+            &ExpenseCategory::Food,
+            &String::from_str(&env, "Kibble"),
+            &String::from_str(&env, "ipfs://hash"),
+            &Vec::new(&env),
+        );
+    }
+
+    // Rewind storage to the v11 layout, where categories were free-form strings
+    env.as_contract(&client.address, || {
+        for (id, name) in [(1u64, "Vaccination"), (2u64, "Grooming")] {
+            let key = DataKey::Expense(id);
+            let mut record: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+            record.set(
+                Symbol::new(&env, "category"),
+                String::from_str(&env, name).into_val(&env),
+            );
+            env.storage().persistent().set(&key, &record);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &11u32);
+    });
+
+    // --- Act ---
+    client.migrate(&admin, &10);
+
+    // --- Assert ---
+    assert_eq!(client.get_schema_version(), 12);
+    assert_eq!(
+        client.get_expense(&1).category,
+        ExpenseCategory::Vaccination
+    );
+    assert_eq!(
+        client.get_expense(&2).category,
+        ExpenseCategory::Custom(String::from_str(&env, "Grooming"))
+    );
+}
+
+#[test]
+fn test_vet_signed_treatments() {
+    // --- Arrange ---
+    let (env, client, admin, _token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let vet_addr = Address::generate(&env);
+    let vet_id = client.register_vet(
+        &vet_addr,
+        &String::from_str(&env, "Dr. Rao"),
+        &String::from_str(&env, "VCI-12345"),
+        &String::from_str(&env, "City Animal Clinic"),
+    );
+    let record = |vet_id: Option<u64>, vet_cosign: bool| {
+        client.try_record_treatment(
+            &feeder_addr,
+            &dog_id,
+            &String::from_str(&env, "Vaccination"),
+            &String::from_str(&env, "Rabies shot"),
+            &0,
+            &FundingSource::SelfFunded,
+            &String::from_str(&env, "Someone"),
+            &vet_id,
+            &vet_cosign,
+            &String::from_str(&env, "Successful"),
+        )
+    };
+
+    // --- Act ---
+    let unverified = record(Some(vet_id), true);
+    let later = record(Some(vet_id), false).unwrap().unwrap();
+    let unverified_countersign = client.try_countersign_treatment(&vet_addr, &later);
+    client.verify_vet(&admin, &vet_id);
+    let cosigned = record(Some(vet_id), true).unwrap().unwrap();
+    let vet_signed = env.auths().iter().any(|(address, _)| *address == vet_addr);
+    client.countersign_treatment(&vet_addr, &later);
+    let unsigned = record(None, false).unwrap().unwrap();
+
+    // --- Assert ---
+    assert_eq!(unverified, Err(Ok(PawChainError::VetNotVerified)));
+    assert_eq!(
+        unverified_countersign,
+        Err(Ok(PawChainError::VetNotVerified))
+    );
+    assert!(vet_signed);
+    let treatment = client.get_treatment(&cosigned);
+    assert_eq!(treatment.vet_id, Some(vet_id));
+    assert!(treatment.vet_attested);
+    assert_eq!(treatment.veterinarian, String::from_str(&env, "Dr. Rao"));
+    assert!(client.get_treatment(&later).vet_attested);
+    assert_eq!(
+        client.try_countersign_treatment(&vet_addr, &later),
+        Err(Ok(PawChainError::AlreadyAttested))
+    );
+    let treatment = client.get_treatment(&unsigned);
+    assert!(!treatment.vet_attested);
+    assert_eq!(treatment.veterinarian, String::from_str(&env, "Someone"));
+    assert_eq!(
+        client.try_countersign_treatment(&vet_addr, &unsigned),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(record(None, true), Err(Ok(PawChainError::VetNotFound)));
+    assert!(client.get_vet(&vet_id).is_verified);
+    assert_eq!(client.list_vets(&0, &10).len(), 1);
+}
+
+#[test]
+fn test_vet_registry() {
+    // --- Arrange ---
+    let (env, client, _admin, _token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let vet_addr = Address::generate(&env);
+    let name = String::from_str(&env, "Dr. Rao");
+    let license = String::from_str(&env, "VCI-12345");
+    let clinic = String::from_str(&env, "City Animal Clinic");
+
+    // --- Act ---
+    let vet_id = client.register_vet(&vet_addr, &name, &license, &clinic);
+
+    // --- Assert ---
+    let vet = client.get_vet(&vet_id);
+    assert_eq!(vet.license_number, license);
+    assert_eq!(vet.clinic, clinic);
+    assert_eq!(vet.wallet_address, vet_addr);
+    assert!(!vet.is_verified);
+    assert_eq!(
+        client.try_register_vet(&vet_addr, &name, &license, &clinic),
+        Err(Ok(PawChainError::VetAlreadyRegistered))
+    );
+    assert_eq!(
+        client.try_verify_vet(&feeder_addr, &vet_id),
+        Err(Ok(PawChainError::Unauthorized))
+    );
+    assert_eq!(client.try_get_vet(&99), Err(Ok(PawChainError::VetNotFound)));
+}
+
+#[test]
+fn test_migrate_adds_treatment_vet_fields() {
+    // --- Arrange ---
+    let (env, client, admin, _token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    let treatment_id = client.record_treatment(
+        &feeder_addr,
+        &dog_id,
+        &String::from_str(&env, "Checkup"),
+        &String::from_str(&env, "General exam"),
+        &0,
+        &FundingSource::SelfFunded,
+        &String::from_str(&env, "Dr. Vet"),
+        &None,
+        &false,
+        &String::from_str(&env, "Successful"),
+    );
+
+    // Rewind storage to the v12 layout, before treatments referenced a vet
+    env.as_contract(&client.address, || {
+        let key = DataKey::Treatment(treatment_id);
+        let mut record: Map<Symbol, Val> = env.storage().persistent().get(&key).unwrap();
+        record.remove(Symbol::new(&env, "vet_id"));
+        record.remove(Symbol::new(&env, "vet_attested"));
+        env.storage().persistent().set(&key, &record);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &12u32);
+    });
+
+    // --- Act ---
+    client.migrate(&admin, &10);
+
+    // --- Assert ---
+    assert_eq!(client.get_schema_version(), 13);
+    let treatment = client.get_treatment(&treatment_id);
+    assert_eq!(treatment.vet_id, None);
+    assert!(!treatment.vet_attested);
+    assert_eq!(treatment.veterinarian, String::from_str(&env, "Dr. Vet"));
+}
+
+// Raw field map for writing records in a layout older than the current types
+fn raw_record(env: &Env, fields: &[(&str, Val)]) -> Map<Symbol, Val> {
+    let mut record = Map::new(env);
+    for (name, value) in fields {
+        record.set(Symbol::new(env, name), *value);
+    }
+    record
+}
+
+#[test]
+fn test_migrate_moves_v0_records_to_persistent_storage() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let feeder_addr = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    let text = |value: &str| String::from_str(&env, value).into_val(&env);
+
+    // Rewrite storage as the unversioned baseline left it: every record in instance storage
+    env.as_contract(&client.address, || {
+        let instance = env.storage().instance();
+        instance.remove(&DataKey::SchemaVersion);
+        instance.remove(&DataKey::Config(ConfigKey::AllowedTokens));
+        let feeder = raw_record(
+            &env,
+            &[
+                ("feeder_id", 1u64.into_val(&env)),
+                ("name", text("Street Paws")),
+                ("organization_type", text("NGO")),
+                ("location", text("Pune")),
+                ("wallet_address", feeder_addr.into_val(&env)),
+                ("registration_number", text("NGO-1")),
+                ("contact_info", text("paws@example.org")),
+                ("registered_date", 1u64.into_val(&env)),
+                ("is_verified", true.into_val(&env)),
+                ("total_received", 500i128.into_val(&env)),
+                ("total_spent", 100i128.into_val(&env)),
+            ],
+        );
+        let dog = raw_record(
+            &env,
+            &[
+                ("dog_id", 1u64.into_val(&env)),
+                ("name", text("Buddy")),
+                ("age", 3u32.into_val(&env)),
+                ("breed", text("Indie")),
+                ("location", text("Pune")),
+                ("health_status", text("Healthy")),
+                ("sickness", text("None")),
+                ("feeder_id", 1u64.into_val(&env)),
+                ("registered_date", 1u64.into_val(&env)),
+                ("last_updated", 1u64.into_val(&env)),
+                ("is_active", true.into_val(&env)),
+            ],
+        );
+        let donation = raw_record(
+            &env,
+            &[
+                ("donation_id", 1u64.into_val(&env)),
+                ("donor_address", donor_addr.into_val(&env)),
+                ("feeder_id", 1u64.into_val(&env)),
+                ("amount", 500i128.into_val(&env)),
+                ("timestamp", 2u64.into_val(&env)),
+                ("purpose", text("Specific Dog")),
+                ("dog_id", Some(1u64).into_val(&env)),
+                ("transaction_hash", text("tx")),
+            ],
+        );
+        let expense = raw_record(
+            &env,
+            &[
+                ("expense_id", 1u64.into_val(&env)),
+                ("feeder_id", 1u64.into_val(&env)),
+                ("amount", 100i128.into_val(&env)),
+                ("category", text("Food")),
+                ("description", text("Kibble")),
+                ("timestamp", 3u64.into_val(&env)),
+                ("receipt_hash", text("ipfs://hash")),
+                (
+                    "dogs_affected",
+                    Vec::from_array(&env, [1u64]).into_val(&env),
+                ),
+                ("verified", false.into_val(&env)),
+            ],
+        );
+        let treatment = raw_record(
+            &env,
+            &[
+                ("treatment_id", 1u64.into_val(&env)),
+                ("dog_id", 1u64.into_val(&env)),
+                ("feeder_id", 1u64.into_val(&env)),
+                ("treatment_type", text("Checkup")),
+                ("description", text("General exam")),
+                ("cost", 40i128.into_val(&env)),
+                ("date", 4u64.into_val(&env)),
+                ("veterinarian", text("Dr. Vet")),
+                ("outcome", text("Successful")),
+            ],
+        );
+        let stats = ActivityStats {
+            feeder_id: 1,
+            dogs_fed: 1,
+            dogs_vaccinated: 0,
+            dogs_spayed: 0,
+            dogs_neutered: 0,
+            dogs_treated: 1,
+            dogs_rescued: 1,
+            dogs_adopted: 0,
+            last_updated: 4,
+        };
+        instance.set(&DataKey::Feeder(1), &feeder);
+        instance.set(&DataKey::FeederStats(1), &stats);
+        instance.set(&DataKey::Dog(1), &dog);
+        instance.set(&DataKey::Donation(1), &donation);
+        instance.set(&DataKey::Expense(1), &expense);
+        instance.set(&DataKey::Treatment(1), &treatment);
+        for count_key in [
+            DataKey::FeederCount,
+            DataKey::DogCount,
+            DataKey::DonationCount,
+            DataKey::ExpenseCount,
+            DataKey::TreatmentCount,
+        ] {
+            instance.set(&count_key, &1u64);
+        }
+    });
+    assert_eq!(client.get_schema_version(), 0);
+
+    // --- Act ---
+    while client.get_schema_version() < SCHEMA_VERSION {
+        client.migrate(&admin, &10);
+    }
+
+    // --- Assert ---
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().instance().has(&DataKey::Dog(1)));
+        assert!(env.storage().persistent().has(&DataKey::Dog(1)));
+    });
+    let feeder = client.get_feeder(&1);
+    assert_eq!(feeder.total_received.get(token_id.clone()), Some(500));
+    assert_eq!(client.get_feeder_stats(&1).dogs_fed, 1);
+    assert_eq!(client.get_donation(&1).token, token_id);
+    assert_eq!(client.get_expense(&1).category, ExpenseCategory::Food);
+    assert_eq!(client.get_treatment(&1).vet_id, None);
+
+    // Existing feeders are found by wallet again
+    assert_eq!(
+        client.try_register_feeder(
+            &feeder_addr,
+            &String::from_str(&env, "Again"),
+            &String::from_str(&env, "NGO"),
+            &String::from_str(&env, "Pune"),
+            &String::from_str(&env, "NGO-1"),
+            &String::from_str(&env, "paws@example.org"),
+        ),
+        Err(Ok(PawChainError::FeederAlreadyRegistered))
+    );
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    assert_eq!(dog_id, 2);
+
+    // Histories and running totals cover the moved records
+    assert_eq!(client.get_feeder_dogs(&1, &0, &10).len(), 2);
+    assert_eq!(client.get_feeder_donations(&1, &0, &10).len(), 1);
+    let summary = client.get_donor_summary(&donor_addr);
+    assert_eq!(summary.total_given.get(token_id.clone()), Some(500));
+    assert_eq!(summary.dogs_supported, 1);
+    let dossier = client.get_dog_dossier(&1);
+    assert_eq!(dossier.total_treatments, 1);
+    assert_eq!(dossier.total_expenses, 1);
+    assert_eq!(dossier.spend.treatment_cost, 40);
+    assert_eq!(dossier.spend.expense_share.get(token_id.clone()), Some(100));
+
+    // The default token is accepted for new donations
+    assert_eq!(
+        client.get_allowed_tokens(),
+        Vec::from_array(&env, [token_id])
+    );
+}
+
+#[test]
+fn test_disallowed_default_token_stops_campaigns_and_subscriptions() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let donor = Address::generate(&env);
+    token_client.mint(&donor, &1000);
+    let deadline = 123456789 + 1000;
+    let campaign_id = client.create_campaign(
+        &feeder_addr,
+        &String::from_str(&env, "Shelter roof"),
+        &None,
+        &500,
+        &deadline,
+    );
+    let subscription_id = client.create_subscription(&donor, &feeder_id, &100, &1000, &None);
+    token.approve(&donor, &client.address, &500, &1000);
+
+    // --- Act ---
+    client.disallow_token(&admin, &token_id);
+
+    // --- Assert ---
+    assert_eq!(
+        client.try_donate_to_campaign(&donor, &campaign_id, &100),
+        Err(Ok(PawChainError::TokenNotAllowed))
+    );
+    assert_eq!(
+        client.try_process_subscription(&subscription_id),
+        Err(Ok(PawChainError::TokenNotAllowed))
+    );
+    assert_eq!(
+        client.try_create_subscription(&donor, &feeder_id, &100, &1000, &None),
+        Err(Ok(PawChainError::TokenNotAllowed))
+    );
+    assert_eq!(
+        client.try_create_campaign(
+            &feeder_addr,
+            &String::from_str(&env, "Vaccines"),
+            &None,
+            &500,
+            &deadline,
+        ),
+        Err(Ok(PawChainError::TokenNotAllowed))
+    );
+    assert_eq!(token.balance(&donor), 1000);
+}
+
+#[test]
+fn test_platform_fee_applies_to_every_donation_path() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, token_client) = setup_test();
+    let token = token::Client::new(&env, &token_id);
+    let (feeder_addr, feeder_id) = register_test_feeder(&env, &client);
+    let treasury = Address::generate(&env);
+    let donor_addr = Address::generate(&env);
+    token_client.mint(&donor_addr, &10000);
+    client.set_fee_config(&admin, &100, &treasury);
+    let purpose = String::from_str(&env, "General");
+
+    // --- Act ---
+    // A single-recipient split is charged like a direct donation
+    let batch_id = client.donate_split(
+        &donor_addr,
+        &Vec::from_array(&env, [(feeder_id, None, 10_000)]),
+        &token_id,
+        &1000,
+        &purpose,
+    );
+    let escrowed_id =
+        client.donate_escrowed(&donor_addr, &feeder_id, &token_id, &2000, &purpose, &None);
+    let subscription_id = client.create_subscription(&donor_addr, &feeder_id, &500, &1000, &None);
+    token.approve(&donor_addr, &client.address, &500, &1000);
+    let subscription_donation_id = client.process_subscription(&subscription_id);
+    let campaign_id = client.create_campaign(
+        &feeder_addr,
+        &String::from_str(&env, "Shelter roof"),
+        &None,
+        &300,
+        &(123456789 + 1000),
+    );
+    client.donate_to_campaign(&donor_addr, &campaign_id, &300);
+    env.ledger().set_timestamp(123456789 + 1000);
+    client.finalize_campaign(&campaign_id);
+    client.record_campaign_payouts(&campaign_id, &0, &10);
+
+    // --- Assert ---
+    assert_eq!(
+        client.get_batch_donations(&batch_id).get(0).unwrap().fee,
+        10
+    );
+    assert_eq!(client.get_donation(&escrowed_id).fee, 20);
+    assert_eq!(client.get_escrow_balance(&feeder_id, &token_id), 1980);
+    assert_eq!(client.get_donation(&subscription_donation_id).fee, 5);
+    let campaign_donation = client
+        .get_feeder_donations(&feeder_id, &3, &1)
+        .get(0)
+        .unwrap();
+    assert_eq!(
+        campaign_donation.purpose,
+        String::from_str(&env, "Campaign")
+    );
+    assert_eq!(campaign_donation.fee, 3);
+    assert_eq!(token.balance(&treasury), 10 + 20 + 5 + 3);
+    assert_eq!(client.get_fee_totals().get(token_id.clone()), Some(38));
+    assert_eq!(token.balance(&feeder_addr), 990 + 495 + 297);
+    assert_eq!(
+        client
+            .get_feeder(&feeder_id)
+            .total_received
+            .get(token_id.clone()),
+        Some(990 + 1980 + 495 + 297)
+    );
+}
+
+#[test]
+fn test_deactivating_dog_closes_emergency_appeal() {
+    // --- Arrange ---
+    let (env, client, _admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let dog_id = register_test_dog(&env, &client, &feeder_addr);
+    client.update_dog_health(
+        &feeder_addr,
+        &dog_id,
+        &String::from_str(&env, "Critical"),
+        &String::from_str(&env, "Hit by a car"),
+    );
+    let deadline = env.ledger().timestamp() + 100;
+    let appeal_id = client.open_emergency(&feeder_addr, &dog_id, &token_id, &500, &deadline);
+
+    // --- Act ---
+    client.deactivate_dog(&feeder_addr, &dog_id, &DogExitReason::Deceased);
+
+    // --- Assert ---
+    let appeal = client.get_emergency(&appeal_id);
+    assert_eq!(appeal.status, EmergencyStatus::DogDeparted);
+    assert_eq!(appeal.closed_at, Some(env.ledger().timestamp()));
+    assert_eq!(client.get_dog_emergency(&dog_id), None);
+    assert_eq!(client.list_active_emergencies(&0, &10).len(), 0);
+    assert_eq!(
+        client.try_open_emergency(&feeder_addr, &dog_id, &token_id, &500, &deadline),
+        Err(Ok(PawChainError::DogInactive))
+    );
+}
+
+#[test]
+fn test_migrate_moves_review_queue_into_index() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    for _ in 0..3 {
+        client.record_expense(
+            &feeder_addr,
+            &token_id,
+            &100,
+            &FundingSource::SelfFunded,
+            &ExpenseCategory::Food,
+            &String::from_str(&env, "Kibble"),
+            &String::from_str(&env, "ipfs://hash"),
+            &Vec::new(&env),
+        );
+    }
+    client.verify_expense(&admin, &1);
+
+    // Rewind storage to the v14 layout, with the queue as one Vec of undecided ids
+    env.as_contract(&client.address, || {
+        let persistent = env.storage().persistent();
+        persistent.remove(&DataKey::IndexLen(IndexKey::PendingExpenses));
+        persistent.remove(&DataKey::IndexBucket(IndexKey::PendingExpenses, 0));
+        persistent.remove(&DataKey::PendingExpensesHead);
+        persistent.set(
+            &LegacyKey::PendingExpenses,
+            &Vec::from_array(&env, [2u64, 3u64]),
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &14u32);
+    });
+
+    // --- Act ---
+    assert_eq!(client.migrate(&admin, &1), 14);
+    assert_eq!(client.migrate(&admin, &1), 15);
+
+    // --- Assert ---
+    let pending = client.list_pending_expenses(&0, &10);
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending.get(0).unwrap().expense_id, 2);
+    client.reject_expense(&admin, &2, &String::from_str(&env, "Duplicate"));
+    let pending = client.list_pending_expenses(&0, &10);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap().expense_id, 3);
+    env.as_contract(&client.address, || {
+        let persistent = env.storage().persistent();
+        assert!(!persistent.has(&LegacyKey::PendingExpenses));
+        assert_eq!(persistent.get(&DataKey::PendingExpensesHead), Some(1u64));
+    });
+}
+
+#[test]
+fn test_migrate_moves_auditors_into_index() {
+    // --- Arrange ---
+    let (env, client, admin, _token_id, _token_client) = setup_test();
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.add_auditor(&admin, &first, &String::from_str(&env, "First"));
+    client.add_auditor(&admin, &second, &String::from_str(&env, "Second"));
+
+    // Rewind storage to the v15 layout, with every auditor in one instance Vec
+    env.as_contract(&client.address, || {
+        let persistent = env.storage().persistent();
+        persistent.remove(&DataKey::IndexLen(IndexKey::Auditors));
+        persistent.remove(&DataKey::IndexBucket(IndexKey::Auditors, 0));
+        let instance = env.storage().instance();
+        instance.set(
+            &DataKey::Auditors,
+            &Vec::from_array(&env, [first.clone(), second.clone()]),
+        );
+        instance.set(&DataKey::SchemaVersion, &15u32);
+    });
+
+    // --- Act ---
+    assert_eq!(client.migrate(&admin, &1), 15);
+    assert_eq!(client.migrate(&admin, &1), 16);
+
+    // --- Assert ---
+    let auditors = client.list_auditors(&0, &10);
+    assert_eq!(auditors.len(), 2);
+    assert_eq!(auditors.get_unchecked(0).address, first);
+    assert_eq!(auditors.get_unchecked(1).address, second);
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().instance().has(&DataKey::Auditors));
+    });
+}
+
+#[test]
+fn test_migrate_moves_open_appeals_into_index() {
+    // --- Arrange ---
+    let (env, client, admin, token_id, _token_client) = setup_test();
+    let (feeder_addr, _) = register_test_feeder(&env, &client);
+    let now = env.ledger().timestamp();
+    for _ in 0..2 {
+        let dog_id = register_test_dog(&env, &client, &feeder_addr);
+        client.update_dog_health(
+            &feeder_addr,
+            &dog_id,
+            &String::from_str(&env, "Critical"),
+            &String::from_str(&env, "Parvovirus"),
+        );
+        client.open_emergency(&feeder_addr, &dog_id, &token_id, &500, &(now + 100));
+    }
+
+    // Rewind storage to the v18 layout, with open appeals in one Vec of ids
+    env.as_contract(&client.address, || {
+        let persistent = env.storage().persistent();
+        persistent.remove(&DataKey::IndexLen(IndexKey::Emergencies));
+        persistent.remove(&DataKey::IndexBucket(IndexKey::Emergencies, 0));
+        persistent.remove(&DataKey::EmergencyHead);
+        persistent.set(
+            &LegacyKey::OpenEmergencies,
+            &Vec::from_array(&env, [1u64, 2u64]),
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &18u32);
+    });
+
+    // --- Act ---
+    assert_eq!(client.migrate(&admin, &1), 18);
+    assert_eq!(client.migrate(&admin, &1), 19);
+
+    // --- Assert ---
+    assert_eq!(client.list_active_emergencies(&0, &10).len(), 2);
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().persistent().has(&LegacyKey::OpenEmergencies));
+    });
+}